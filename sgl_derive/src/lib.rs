@@ -0,0 +1,103 @@
+//! `#[derive(Vertex)]` for sgl
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Error, Fields, Member};
+
+/// Implements `sgl::Vertex`, one attribute per field in declaration order.
+///
+/// Every field type must implement `sgl::VertexField`. Fields can be annotated with
+/// `#[vertex(normalized)]` to upload integers as normalized floats, or `#[vertex(float)]` to
+/// convert them to floats without normalizing.
+#[proc_macro_derive(Vertex, attributes(vertex))]
+pub fn derive_vertex(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+#[derive(Default)]
+struct FieldOptions {
+    normalized: bool,
+    float: bool,
+}
+
+fn field_options(field: &syn::Field) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("vertex")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("normalized") {
+                options.normalized = true;
+                Ok(())
+            } else if meta.path.is_ident("float") {
+                options.float = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `normalized` or `float`"))
+            }
+        })?;
+    }
+    Ok(options)
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            Fields::Unnamed(fields) => &fields.unnamed,
+            Fields::Unit => {
+                return Err(Error::new(
+                    input.span(),
+                    "`Vertex` can't be derived for unit structs",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new(
+                input.span(),
+                "`Vertex` can only be derived for structs",
+            ))
+        }
+    };
+
+    let attributes = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let options = field_options(field)?;
+            let ty = &field.ty;
+            let member = match &field.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(i.into()),
+            };
+            let kind = if options.normalized || options.float {
+                quote!(::sgl::AttributeKind::Float)
+            } else {
+                quote!(<#ty as ::sgl::VertexField>::KIND)
+            };
+            let normalized = options.normalized;
+
+            Ok(quote! {
+                ::sgl::VertexAttribute {
+                    size: <#ty as ::sgl::VertexField>::SIZE,
+                    ty: <#ty as ::sgl::VertexField>::TYPE,
+                    kind: #kind,
+                    normalized: #normalized,
+                    offset: ::core::mem::offset_of!(Self, #member),
+                }
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    Ok(quote! {
+        impl #impl_generics ::sgl::Vertex for #name #ty_generics #where_clause {
+            const ATTRIBUTES: &'static [::sgl::VertexAttribute] = &[#(#attributes),*];
+        }
+    })
+}
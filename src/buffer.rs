@@ -1,6 +1,13 @@
 use super::{Type, Usage};
+use bytemuck::Pod;
+use enumflags2::{bitflags, BitFlags};
 use shrinkwraprs::Shrinkwrap;
-use std::{os::raw::c_void, ptr::null};
+use std::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    os::raw::c_void,
+    ptr::null,
+};
 
 #[repr(transparent)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Shrinkwrap)]
@@ -18,23 +25,156 @@ impl VertexArray {
     pub const NONE: Self = Self(0);
 }
 
+/// A [`Buffer`] that is deleted when dropped
+#[derive(Debug, PartialEq, Eq, Hash, Shrinkwrap)]
+pub struct OwnedBuffer(Buffer);
+
+impl OwnedBuffer {
+    pub fn new() -> Self {
+        Self(gen_buffer())
+    }
+
+    /// Takes ownership of `buffer`, it will be deleted when the returned value is dropped
+    pub fn from_raw(buffer: Buffer) -> Self {
+        Self(buffer)
+    }
+
+    pub fn handle(&self) -> Buffer {
+        self.0
+    }
+
+    /// Releases ownership without deleting, the caller is responsible for [`delete_buffer`]
+    pub fn into_raw(self) -> Buffer {
+        let buffer = self.0;
+        std::mem::forget(self);
+        buffer
+    }
+}
+
+impl Default for OwnedBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for OwnedBuffer {
+    fn drop(&mut self) {
+        delete_buffer(self.0)
+    }
+}
+
+/// A [`VertexArray`] that is deleted when dropped
+#[derive(Debug, PartialEq, Eq, Hash, Shrinkwrap)]
+pub struct OwnedVertexArray(VertexArray);
+
+impl OwnedVertexArray {
+    pub fn new() -> Self {
+        Self(gen_vertex_array())
+    }
+
+    /// Takes ownership of `array`, it will be deleted when the returned value is dropped
+    pub fn from_raw(array: VertexArray) -> Self {
+        Self(array)
+    }
+
+    pub fn handle(&self) -> VertexArray {
+        self.0
+    }
+
+    /// Releases ownership without deleting, the caller is responsible for [`delete_vertex_array`]
+    pub fn into_raw(self) -> VertexArray {
+        let array = self.0;
+        std::mem::forget(self);
+        array
+    }
+}
+
+impl Default for OwnedVertexArray {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for OwnedVertexArray {
+    fn drop(&mut self) {
+        delete_vertex_array(self.0)
+    }
+}
+
+gl_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Hash)]
+    pub enum BufferKind {
+        Array = rgl::ARRAY_BUFFER,
+        AtomicCounter = rgl::ATOMIC_COUNTER_BUFFER,
+        CopyRead = rgl::COPY_READ_BUFFER,
+        CopyWrite = rgl::COPY_WRITE_BUFFER,
+        DispatchIndirect = rgl::DISPATCH_INDIRECT_BUFFER,
+        DrawIndirect = rgl::DRAW_INDIRECT_BUFFER,
+        ElementArray = rgl::ELEMENT_ARRAY_BUFFER,
+        PixelPack = rgl::PIXEL_PACK_BUFFER,
+        PixelUnpack = rgl::PIXEL_UNPACK_BUFFER,
+        QueryBuffer = rgl::QUERY_BUFFER,
+        ShaderStorage = rgl::SHADER_STORAGE_BUFFER,
+        Texture = rgl::TEXTURE_BUFFER,
+        TransformFeedback = rgl::TRANSFORM_FEEDBACK_BUFFER,
+        Uniform = rgl::UNIFORM_BUFFER,
+    }
+}
+
+gl_enum! {
+    /// The targets accepted by [`bind_buffer_base`] and [`bind_buffer_range`]
+    #[derive(Debug, Clone, Copy, PartialEq, Hash)]
+    pub enum IndexedBufferKind {
+        AtomicCounter = rgl::ATOMIC_COUNTER_BUFFER,
+        ShaderStorage = rgl::SHADER_STORAGE_BUFFER,
+        TransformFeedback = rgl::TRANSFORM_FEEDBACK_BUFFER,
+        Uniform = rgl::UNIFORM_BUFFER,
+    }
+}
+
+impl IndexedBufferKind {
+    /// Required alignment in bytes of the `offset` passed to [`bind_buffer_range`], a
+    /// `glGetIntegerv` for uniform and shader storage buffers
+    pub fn offset_alignment(self) -> usize {
+        let pname = match self {
+            Self::Uniform => rgl::UNIFORM_BUFFER_OFFSET_ALIGNMENT,
+            Self::ShaderStorage => rgl::SHADER_STORAGE_BUFFER_OFFSET_ALIGNMENT,
+            // fixed by the spec
+            Self::AtomicCounter | Self::TransformFeedback => return 4,
+        };
+        let mut alignment: i32 = 0;
+        unsafe { gl!(GetIntegerv(pname, &mut alignment)) };
+        alignment.max(1) as usize
+    }
+}
+
+impl From<IndexedBufferKind> for BufferKind {
+    fn from(kind: IndexedBufferKind) -> Self {
+        match kind {
+            IndexedBufferKind::AtomicCounter => Self::AtomicCounter,
+            IndexedBufferKind::ShaderStorage => Self::ShaderStorage,
+            IndexedBufferKind::TransformFeedback => Self::TransformFeedback,
+            IndexedBufferKind::Uniform => Self::Uniform,
+        }
+    }
+}
+
+#[bitflags]
 #[repr(u32)]
 #[derive(Debug, Clone, Copy, PartialEq, Hash)]
-pub enum BufferKind {
-    Array = rgl::ARRAY_BUFFER,
-    AtomicCounter = rgl::ATOMIC_COUNTER_BUFFER,
-    CopyRead = rgl::COPY_READ_BUFFER,
-    CopyWrite = rgl::COPY_WRITE_BUFFER,
-    DispatchIndirect = rgl::DISPATCH_INDIRECT_BUFFER,
-    DrawIndirect = rgl::DRAW_INDIRECT_BUFFER,
-    ElementArray = rgl::ELEMENT_ARRAY_BUFFER,
-    PixelPack = rgl::PIXEL_PACK_BUFFER,
+pub enum BufferStorageFlags {
+    DynamicStorage = rgl::DYNAMIC_STORAGE_BIT,
+    MapRead = rgl::MAP_READ_BIT,
+    MapWrite = rgl::MAP_WRITE_BIT,
+    MapPersistent = rgl::MAP_PERSISTENT_BIT,
+    MapCoherent = rgl::MAP_COHERENT_BIT,
+    ClientStorage = rgl::CLIENT_STORAGE_BIT,
 }
 
 pub fn gen_buffers<const N: usize>() -> [Buffer; N] {
     assert!(N < u32::MAX as usize);
     let mut buffers = [Buffer(0); N];
-    unsafe { rgl::GenBuffers(N as i32, buffers.as_mut_ptr() as *mut u32) };
+    unsafe { gl!(GenBuffers(N as i32, buffers.as_mut_ptr() as *mut u32)) };
     buffers
 }
 
@@ -43,23 +183,164 @@ pub fn gen_buffer() -> Buffer {
 }
 
 pub fn bind_buffer(target: BufferKind, buffer: Buffer) {
-    unsafe { rgl::BindBuffer(target as u32, buffer.0) }
+    unsafe { gl!(BindBuffer(target as u32, buffer.0)) }
+}
+
+/// Binds the whole of `buffer` to binding point `index` of `target`, as well as to `target` itself
+pub fn bind_buffer_base(target: IndexedBufferKind, index: u32, buffer: Buffer) {
+    unsafe { gl!(BindBufferBase(target as u32, index, buffer.0)) }
+}
+
+/// Binds `size` bytes of `buffer` starting at `offset` to binding point `index` of `target`.
+///
+/// `offset` must be a multiple of [`IndexedBufferKind::offset_alignment`], which is only checked
+/// in debug builds since looking it up can stall.
+pub fn bind_buffer_range(
+    target: IndexedBufferKind,
+    index: u32,
+    buffer: Buffer,
+    offset: usize,
+    size: usize,
+) {
+    if cfg!(debug_assertions) {
+        let alignment = target.offset_alignment();
+        assert!(
+            offset.is_multiple_of(alignment),
+            "offset {} of {:?} buffer range isn't aligned to {} bytes",
+            offset,
+            target,
+            alignment
+        );
+    }
+    assert!(size > 0, "buffer range can't be empty");
+    if matches!(
+        target,
+        IndexedBufferKind::AtomicCounter | IndexedBufferKind::TransformFeedback
+    ) {
+        assert!(
+            size.is_multiple_of(4),
+            "{:?} buffer range size must be a multiple of 4",
+            target
+        );
+    }
+
+    unsafe {
+        gl!(BindBufferRange(
+            target as u32,
+            index,
+            buffer.0,
+            offset as isize,
+            size as isize,
+        ))
+    }
 }
 
 pub fn buffer_data<T>(target: BufferKind, data: &T, usage: Usage) {
     let size = std::mem::size_of_val(data) as isize;
     let data: *const std::os::raw::c_void = data as *const T as *const _;
-    unsafe { rgl::BufferData(target as u32, size, data, usage as u32) }
+    unsafe { gl!(BufferData(target as u32, size, data, usage as u32)) }
 }
 
 pub unsafe fn buffer_data_ptr(target: BufferKind, size: isize, data: *const c_void, usage: Usage) {
-    rgl::BufferData(target as u32, size, data, usage as u32)
+    gl!(BufferData(target as u32, size, data, usage as u32))
+}
+
+pub fn buffer_data_slice<T: Pod>(target: BufferKind, data: &[T], usage: Usage) {
+    let size = std::mem::size_of_val(data) as isize;
+    unsafe {
+        gl!(BufferData(
+            target as u32,
+            size,
+            data.as_ptr() as *const _,
+            usage as u32
+        ))
+    }
+}
+
+/// Size in bytes of the buffer bound to `target`
+pub fn get_buffer_size(target: BufferKind) -> usize {
+    let mut size: i64 = 0;
+    unsafe {
+        gl!(GetBufferParameteri64v(
+            target as u32,
+            rgl::BUFFER_SIZE,
+            &mut size
+        ))
+    };
+    size as usize
+}
+
+fn assert_in_bounds(target: BufferKind, offset: usize, len: usize) {
+    let size = get_buffer_size(target);
+    assert!(
+        offset.checked_add(len).is_some_and(|end| end <= size),
+        "range {}..{} is out of bounds of the {} byte {:?} buffer",
+        offset,
+        offset.saturating_add(len),
+        size,
+        target
+    );
+}
+
+/// Replaces part of the buffer bound to `target`, `offset` is in bytes
+pub fn buffer_sub_data<T: Pod>(target: BufferKind, offset: usize, data: &[T]) {
+    let size = std::mem::size_of_val(data);
+    assert_in_bounds(target, offset, size);
+    unsafe {
+        gl!(BufferSubData(
+            target as u32,
+            offset as isize,
+            size as isize,
+            data.as_ptr() as *const _,
+        ))
+    }
+}
+
+/// Reads part of the buffer bound to `target` into `data`, `offset` is in bytes
+pub fn get_buffer_sub_data<T: Pod>(target: BufferKind, offset: usize, data: &mut [T]) {
+    let size = std::mem::size_of_val(data);
+    assert_in_bounds(target, offset, size);
+    unsafe {
+        gl!(GetBufferSubData(
+            target as u32,
+            offset as isize,
+            size as isize,
+            data.as_mut_ptr() as *mut _,
+        ))
+    }
+}
+
+/// Allocates immutable storage for the buffer bound to `target`, initialised with `data`
+pub fn buffer_storage<T: Pod>(target: BufferKind, data: &[T], flags: BitFlags<BufferStorageFlags>) {
+    let size = std::mem::size_of_val(data);
+    assert!(size > 0, "buffer storage can't be empty");
+    unsafe {
+        gl!(BufferStorage(
+            target as u32,
+            size as isize,
+            data.as_ptr() as *const _,
+            flags.bits(),
+        ))
+    }
+}
+
+/// Allocates `size` bytes of uninitialised immutable storage for the buffer bound to `target`
+pub fn buffer_storage_uninit(target: BufferKind, size: usize, flags: BitFlags<BufferStorageFlags>) {
+    assert!(size > 0, "buffer storage can't be empty");
+    unsafe {
+        gl!(BufferStorage(
+            target as u32,
+            size as isize,
+            null(),
+            flags.bits()
+        ))
+    }
 }
 
 pub fn gen_vertex_arrays<const N: usize>() -> [VertexArray; N] {
     assert!(N < u32::MAX as usize);
     let mut array = [VertexArray(0); N];
-    unsafe { rgl::GenVertexArrays(N as i32, array.as_mut_ptr() as *mut u32) };
+    unsafe { gl!(GenVertexArrays(N as i32, array.as_mut_ptr() as *mut u32)) };
     array
 }
 
@@ -68,25 +349,313 @@ pub fn gen_vertex_array() -> VertexArray {
 }
 
 pub fn bind_vertex_array(vertex_array: VertexArray) {
-    unsafe { rgl::BindVertexArray(vertex_array.0) }
+    unsafe { gl!(BindVertexArray(vertex_array.0)) }
 }
 
+/// Deletes every buffer in `buffers`, skipping [`Buffer::NONE`]
 pub fn delete_buffers(buffers: &[Buffer]) {
-    unsafe { rgl::DeleteBuffers(buffers.len() as i32 - 1, buffers.as_ptr() as *const u32) }
+    let names: Vec<u32> = buffers
+        .iter()
+        .filter(|b| **b != Buffer::NONE)
+        .map(|b| b.0)
+        .collect();
+    if names.is_empty() {
+        return;
+    }
+    assert!(names.len() < i32::MAX as usize);
+    unsafe { gl!(DeleteBuffers(names.len() as i32, names.as_ptr())) }
 }
 
 pub fn delete_buffer(buffer: Buffer) {
     delete_buffers(&[buffer])
 }
 
+/// Deletes every vertex array in `arrays`, skipping [`VertexArray::NONE`]
 pub fn delete_vertex_arrays(arrays: &[VertexArray]) {
-    unsafe { rgl::DeleteVertexArrays(arrays.len() as i32 - 1, arrays.as_ptr() as *const u32) }
+    let names: Vec<u32> = arrays
+        .iter()
+        .filter(|a| **a != VertexArray::NONE)
+        .map(|a| a.0)
+        .collect();
+    if names.is_empty() {
+        return;
+    }
+    assert!(names.len() < i32::MAX as usize);
+    unsafe { gl!(DeleteVertexArrays(names.len() as i32, names.as_ptr())) }
 }
 
 pub fn delete_vertex_array(array: VertexArray) {
     delete_vertex_arrays(&[array])
 }
 
+/// Like [`gen_buffers`] but the buffers are created right away, so they can be used with the
+/// `named_*` functions without being bound first
+pub fn create_buffers<const N: usize>() -> [Buffer; N] {
+    assert!(N < u32::MAX as usize);
+    super::assert_dsa_loaded(rgl::CreateBuffers::is_loaded(), "glCreateBuffers");
+    let mut buffers = [Buffer(0); N];
+    unsafe { gl!(CreateBuffers(N as i32, buffers.as_mut_ptr() as *mut u32)) };
+    buffers
+}
+
+pub fn create_buffer() -> Buffer {
+    create_buffers::<1>()[0]
+}
+
+pub fn named_buffer_data<T: Pod>(buffer: Buffer, data: &[T], usage: Usage) {
+    let size = std::mem::size_of_val(data) as isize;
+    unsafe {
+        gl!(NamedBufferData(
+            buffer.0,
+            size,
+            data.as_ptr() as *const _,
+            usage as u32
+        ))
+    }
+}
+
+/// Size in bytes of `buffer`
+pub fn get_named_buffer_size(buffer: Buffer) -> usize {
+    let mut size: i64 = 0;
+    unsafe {
+        gl!(GetNamedBufferParameteri64v(
+            buffer.0,
+            rgl::BUFFER_SIZE,
+            &mut size
+        ))
+    };
+    size as usize
+}
+
+/// Writes `data` to `buffer` starting at `offset` bytes, panics if it doesn't fit
+pub fn named_buffer_sub_data<T: Pod>(buffer: Buffer, offset: usize, data: &[T]) {
+    let size = std::mem::size_of_val(data);
+    let buffer_size = get_named_buffer_size(buffer);
+    assert!(
+        offset
+            .checked_add(size)
+            .is_some_and(|end| end <= buffer_size),
+        "range {}..{} is out of bounds of a {} byte buffer",
+        offset,
+        offset.saturating_add(size),
+        buffer_size
+    );
+    unsafe {
+        gl!(NamedBufferSubData(
+            buffer.0,
+            offset as isize,
+            size as isize,
+            data.as_ptr() as *const _,
+        ))
+    }
+}
+
+/// Allocates immutable storage for `buffer`, initialised with `data`
+pub fn named_buffer_storage<T: Pod>(
+    buffer: Buffer,
+    data: &[T],
+    flags: BitFlags<BufferStorageFlags>,
+) {
+    let size = std::mem::size_of_val(data);
+    assert!(size > 0, "buffer storage can't be empty");
+    unsafe {
+        gl!(NamedBufferStorage(
+            buffer.0,
+            size as isize,
+            data.as_ptr() as *const _,
+            flags.bits(),
+        ))
+    }
+}
+
+impl Buffer {
+    /// See [`create_buffer`]
+    pub fn create() -> Self {
+        create_buffer()
+    }
+
+    /// See [`named_buffer_data`]
+    pub fn data<T: Pod>(self, data: &[T], usage: Usage) {
+        named_buffer_data(self, data, usage)
+    }
+
+    /// See [`named_buffer_sub_data`]
+    pub fn sub_data<T: Pod>(self, offset: usize, data: &[T]) {
+        named_buffer_sub_data(self, offset, data)
+    }
+
+    /// See [`named_buffer_storage`]
+    pub fn storage<T: Pod>(self, data: &[T], flags: BitFlags<BufferStorageFlags>) {
+        named_buffer_storage(self, data, flags)
+    }
+
+    /// See [`get_named_buffer_size`]
+    pub fn size(self) -> usize {
+        get_named_buffer_size(self)
+    }
+}
+
+/// Like [`gen_vertex_arrays`] but the vertex arrays are created right away, so they can be used
+/// with the `vertex_array_*` functions without being bound first
+pub fn create_vertex_arrays<const N: usize>() -> [VertexArray; N] {
+    assert!(N < u32::MAX as usize);
+    super::assert_dsa_loaded(rgl::CreateVertexArrays::is_loaded(), "glCreateVertexArrays");
+    let mut arrays = [VertexArray(0); N];
+    unsafe {
+        gl!(CreateVertexArrays(
+            N as i32,
+            arrays.as_mut_ptr() as *mut u32
+        ))
+    };
+    arrays
+}
+
+pub fn create_vertex_array() -> VertexArray {
+    create_vertex_arrays::<1>()[0]
+}
+
+pub fn enable_vertex_array_attrib(array: VertexArray, index: u32) {
+    unsafe { gl!(EnableVertexArrayAttrib(array.0, index)) }
+}
+
+/// Describes attribute `index` of `array`, read as floats by the shader. `relative_offset` is in
+/// bytes from the start of the vertex in the buffer bound with [`vertex_array_vertex_buffer`].
+pub fn vertex_array_attrib_format(
+    array: VertexArray,
+    index: u32,
+    size: i32,
+    ty: Type,
+    normalized: bool,
+    relative_offset: u32,
+) {
+    unsafe {
+        gl!(VertexArrayAttribFormat(
+            array.0,
+            index,
+            size,
+            ty as u32,
+            normalized as u8,
+            relative_offset,
+        ))
+    }
+}
+
+/// Like [`vertex_array_attrib_format`] but read as `int`/`uint` by the shader
+pub fn vertex_array_attrib_i_format(
+    array: VertexArray,
+    index: u32,
+    size: i32,
+    ty: Type,
+    relative_offset: u32,
+) {
+    debug_assert!(ty.valid_for_ipointer());
+    unsafe {
+        gl!(VertexArrayAttribIFormat(
+            array.0,
+            index,
+            size,
+            ty as u32,
+            relative_offset
+        ))
+    }
+}
+
+/// Like [`vertex_array_attrib_format`] but read as `double` by the shader
+pub fn vertex_array_attrib_l_format(
+    array: VertexArray,
+    index: u32,
+    size: i32,
+    relative_offset: u32,
+) {
+    unsafe {
+        gl!(VertexArrayAttribLFormat(
+            array.0,
+            index,
+            size,
+            rgl::DOUBLE,
+            relative_offset
+        ))
+    }
+}
+
+/// Makes attribute `index` of `array` read from buffer binding point `binding`
+pub fn vertex_array_attrib_binding(array: VertexArray, index: u32, binding: u32) {
+    unsafe { gl!(VertexArrayAttribBinding(array.0, index, binding)) }
+}
+
+/// Binds `buffer` to binding point `binding` of `array`, with vertices `stride` bytes apart
+/// starting at `offset` bytes
+pub fn vertex_array_vertex_buffer(
+    array: VertexArray,
+    binding: u32,
+    buffer: Buffer,
+    offset: usize,
+    stride: i32,
+) {
+    unsafe {
+        gl!(VertexArrayVertexBuffer(
+            array.0,
+            binding,
+            buffer.0,
+            offset as isize,
+            stride
+        ))
+    }
+}
+
+pub fn vertex_array_element_buffer(array: VertexArray, buffer: Buffer) {
+    unsafe { gl!(VertexArrayElementBuffer(array.0, buffer.0)) }
+}
+
+impl VertexArray {
+    /// See [`create_vertex_array`]
+    pub fn create() -> Self {
+        create_vertex_array()
+    }
+
+    /// See [`enable_vertex_array_attrib`]
+    pub fn enable_attrib(self, index: u32) {
+        enable_vertex_array_attrib(self, index)
+    }
+
+    /// See [`vertex_array_attrib_format`]
+    pub fn attrib_format(
+        self,
+        index: u32,
+        size: i32,
+        ty: Type,
+        normalized: bool,
+        relative_offset: u32,
+    ) {
+        vertex_array_attrib_format(self, index, size, ty, normalized, relative_offset)
+    }
+
+    /// See [`vertex_array_attrib_i_format`]
+    pub fn attrib_i_format(self, index: u32, size: i32, ty: Type, relative_offset: u32) {
+        vertex_array_attrib_i_format(self, index, size, ty, relative_offset)
+    }
+
+    /// See [`vertex_array_attrib_l_format`]
+    pub fn attrib_l_format(self, index: u32, size: i32, relative_offset: u32) {
+        vertex_array_attrib_l_format(self, index, size, relative_offset)
+    }
+
+    /// See [`vertex_array_attrib_binding`]
+    pub fn attrib_binding(self, index: u32, binding: u32) {
+        vertex_array_attrib_binding(self, index, binding)
+    }
+
+    /// See [`vertex_array_vertex_buffer`]
+    pub fn vertex_buffer(self, binding: u32, buffer: Buffer, offset: usize, stride: i32) {
+        vertex_array_vertex_buffer(self, binding, buffer, offset, stride)
+    }
+
+    /// See [`vertex_array_element_buffer`]
+    pub fn element_buffer(self, buffer: Buffer) {
+        vertex_array_element_buffer(self, buffer)
+    }
+}
+
 pub unsafe fn vertex_attrib_pointer_raw(
     index: u32,
     size: i32,
@@ -95,14 +664,14 @@ pub unsafe fn vertex_attrib_pointer_raw(
     stride: i32,
     pointer: Option<*const c_void>,
 ) {
-    rgl::VertexAttribPointer(
+    gl!(VertexAttribPointer(
         index,
         size,
         ty as u32,
         normalized as u8,
         stride,
         if let Some(p) = pointer { p } else { null() },
-    )
+    ))
 }
 
 pub fn vertex_attrib_pointer(
@@ -114,14 +683,14 @@ pub fn vertex_attrib_pointer(
     offset: isize,
 ) {
     unsafe {
-        rgl::VertexAttribPointer(
+        gl!(VertexAttribPointer(
             index,
             size,
             ty as u32,
             normalized as u8,
             stride,
             offset as *const _,
-        )
+        ))
     }
 }
 
@@ -132,24 +701,372 @@ pub unsafe fn vertex_attrib_ipointer(
     stride: i32,
     pointer: *const c_void,
 ) {
-    debug_assert!(matches!(
-        ty,
-        Type::Byte
-            | Type::UnsignedByte
-            | Type::Short
-            | Type::UnsignedShort
-            | Type::Int
-            | Type::UnsignedInt
-    ));
+    debug_assert!(ty.valid_for_ipointer());
 
-    rgl::VertexAttribIPointer(index, size, ty as u32, stride, pointer)
+    gl!(VertexAttribIPointer(index, size, ty as u32, stride, pointer))
 }
 
 // The type param is dropped because it only accepts `GL_DOUBLE` anyway
 pub unsafe fn vertex_attrib_lpointer(index: u32, size: i32, stride: i32, pointer: *const c_void) {
-    rgl::VertexAttribLPointer(index, size, rgl::DOUBLE, stride, pointer)
+    gl!(VertexAttribLPointer(index, size, rgl::DOUBLE, stride, pointer))
 }
 
 pub fn enable_vertex_attrib_array(index: u32) {
-    unsafe { rgl::EnableVertexAttribArray(index) }
+    unsafe { gl!(EnableVertexAttribArray(index)) }
+}
+
+/// Makes attribute `index` advance once every `divisor` instances instead of every vertex, 0
+/// goes back to per-vertex
+pub fn vertex_attrib_divisor(index: u32, divisor: u32) {
+    unsafe { gl!(VertexAttribDivisor(index, divisor)) }
+}
+
+/// Describes attribute `index` of the bound vertex array, read as floats by the shader.
+/// `relative_offset` is in bytes from the start of the vertex in the buffer bound with
+/// [`bind_vertex_buffer`].
+pub fn vertex_attrib_format(
+    index: u32,
+    size: i32,
+    ty: Type,
+    normalized: bool,
+    relative_offset: u32,
+) {
+    unsafe {
+        gl!(VertexAttribFormat(
+            index,
+            size,
+            ty as u32,
+            normalized as u8,
+            relative_offset,
+        ))
+    }
+}
+
+/// Like [`vertex_attrib_format`] but read as `int`/`uint` by the shader
+pub fn vertex_attrib_i_format(index: u32, size: i32, ty: Type, relative_offset: u32) {
+    debug_assert!(ty.valid_for_ipointer());
+    unsafe { gl!(VertexAttribIFormat(index, size, ty as u32, relative_offset)) }
+}
+
+/// Like [`vertex_attrib_format`] but read as `double` by the shader
+pub fn vertex_attrib_l_format(index: u32, size: i32, relative_offset: u32) {
+    unsafe {
+        gl!(VertexAttribLFormat(
+            index,
+            size,
+            rgl::DOUBLE,
+            relative_offset
+        ))
+    }
+}
+
+/// Makes attribute `index` of the bound vertex array read from buffer binding point `binding`
+pub fn vertex_attrib_binding(index: u32, binding: u32) {
+    unsafe { gl!(VertexAttribBinding(index, binding)) }
+}
+
+/// Binds `buffer` to binding point `binding` of the bound vertex array, with vertices `stride`
+/// bytes apart starting at `offset` bytes
+pub fn bind_vertex_buffer(binding: u32, buffer: Buffer, offset: usize, stride: i32) {
+    unsafe { gl!(BindVertexBuffer(binding, buffer.0, offset as isize, stride)) }
+}
+
+/// Binds `buffers[i]` to binding point `first + i`, see [`bind_vertex_buffer`]
+pub fn bind_vertex_buffers(first: u32, buffers: &[Buffer], offsets: &[usize], strides: &[i32]) {
+    assert!(
+        buffers.len() == offsets.len() && buffers.len() == strides.len(),
+        "got {} buffers, {} offsets and {} strides",
+        buffers.len(),
+        offsets.len(),
+        strides.len()
+    );
+    if buffers.is_empty() {
+        return;
+    }
+    assert!(buffers.len() < i32::MAX as usize);
+    let offsets: Vec<isize> = offsets.iter().map(|o| *o as isize).collect();
+    unsafe {
+        gl!(BindVertexBuffers(
+            first,
+            buffers.len() as i32,
+            buffers.as_ptr() as *const u32,
+            offsets.as_ptr(),
+            strides.as_ptr(),
+        ))
+    }
+}
+
+/// Makes binding point `binding` advance once every `divisor` instances instead of every vertex,
+/// 0 goes back to per-vertex
+pub fn vertex_binding_divisor(binding: u32, divisor: u32) {
+    unsafe { gl!(VertexBindingDivisor(binding, divisor)) }
+}
+
+#[bitflags]
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
+pub enum MapAccess {
+    Read = rgl::MAP_READ_BIT,
+    Write = rgl::MAP_WRITE_BIT,
+    InvalidateRange = rgl::MAP_INVALIDATE_RANGE_BIT,
+    InvalidateBuffer = rgl::MAP_INVALIDATE_BUFFER_BIT,
+    FlushExplicit = rgl::MAP_FLUSH_EXPLICIT_BIT,
+    Unsynchronized = rgl::MAP_UNSYNCHRONIZED_BIT,
+    Persistent = rgl::MAP_PERSISTENT_BIT,
+    Coherent = rgl::MAP_COHERENT_BIT,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MapError {
+    /// `glMapBufferRange` returned null, e.g. because the buffer is already mapped
+    Failed,
+    /// `glUnmapBuffer` reported that the data store was corrupted while mapped, the buffer's
+    /// contents are undefined and must be uploaded again
+    Corrupted,
+}
+
+impl std::fmt::Display for MapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Failed => write!(f, "failed to map buffer"),
+            Self::Corrupted => write!(f, "buffer data store was corrupted while mapped"),
+        }
+    }
+}
+
+impl std::error::Error for MapError {}
+
+/// Whether a [`MappedBuffer`] may be read, written or both, which decides what it derefs to
+pub trait MapMode {
+    /// The [`MapAccess::Read`]/[`MapAccess::Write`] bits the buffer is mapped with
+    fn access() -> BitFlags<MapAccess>;
+}
+
+/// Maps with [`MapAccess::Read`], the mapping derefs to `&[T]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MapRead;
+
+/// Maps with [`MapAccess::Write`] only. GL leaves reading such a mapping undefined, so it
+/// doesn't deref and is written with [`MappedBuffer::copy_from_slice`] or
+/// [`MappedBuffer::write`] instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MapWrite;
+
+/// Maps with both [`MapAccess::Read`] and [`MapAccess::Write`], the mapping derefs to `&mut [T]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MapReadWrite;
+
+impl MapMode for MapRead {
+    fn access() -> BitFlags<MapAccess> {
+        MapAccess::Read.into()
+    }
+}
+
+impl MapMode for MapWrite {
+    fn access() -> BitFlags<MapAccess> {
+        MapAccess::Write.into()
+    }
+}
+
+impl MapMode for MapReadWrite {
+    fn access() -> BitFlags<MapAccess> {
+        MapAccess::Read | MapAccess::Write
+    }
+}
+
+/// A mapped range of a buffer, unmapped when dropped.
+///
+/// `A` is one of [`MapRead`], [`MapWrite`] or [`MapReadWrite`]. When mapped through
+/// [`map_buffer_range`] the [`OwnedBuffer`] stays mutably borrowed, so it can't be deleted or
+/// respecified while mapped. Dropping ignores data corruption, use [`MappedBuffer::unmap`] to
+/// find out about it.
+#[derive(Debug)]
+pub struct MappedBuffer<'a, T: Pod, A: MapMode = MapReadWrite> {
+    target: BufferKind,
+    buffer: Buffer,
+    ptr: *mut T,
+    len: usize,
+    access: BitFlags<MapAccess>,
+    _marker: PhantomData<(&'a mut [T], A)>,
+}
+
+impl<'a, T: Pod, A: MapMode> MappedBuffer<'a, T, A> {
+    pub fn access(&self) -> BitFlags<MapAccess> {
+        self.access
+    }
+
+    /// In elements
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn as_ptr(&self) -> *const T {
+        self.ptr
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr
+    }
+
+    /// Flushes `len` elements starting at element `offset`, requires [`MapAccess::FlushExplicit`]
+    pub fn flush(&self, offset: usize, len: usize) {
+        assert!(self.access.contains(MapAccess::FlushExplicit));
+        assert!(
+            offset.checked_add(len).is_some_and(|end| end <= self.len),
+            "flushed range is out of bounds of the mapping"
+        );
+        let size = std::mem::size_of::<T>();
+        bind_buffer(self.target, self.buffer);
+        flush_mapped_buffer_range(self.target, offset * size, len * size);
+    }
+
+    pub fn unmap(self) -> Result<(), MapError> {
+        let result = self.unmap_inner();
+        std::mem::forget(self);
+        result
+    }
+
+    fn unmap_inner(&self) -> Result<(), MapError> {
+        bind_buffer(self.target, self.buffer);
+        if unsafe { gl!(UnmapBuffer(self.target as u32)) } == rgl::TRUE {
+            Ok(())
+        } else {
+            Err(MapError::Corrupted)
+        }
+    }
+}
+
+impl<'a, T: Pod> MappedBuffer<'a, T, MapWrite> {
+    /// Writes `value` to element `index`
+    pub fn write(&mut self, index: usize, value: T) {
+        assert!(
+            index < self.len,
+            "index {} is out of bounds of a {} element mapping",
+            index,
+            self.len
+        );
+        unsafe { self.ptr.add(index).write(value) }
+    }
+
+    /// Overwrites the whole mapping, `data` must have [`MappedBuffer::len`] elements
+    pub fn copy_from_slice(&mut self, data: &[T]) {
+        assert_eq!(
+            data.len(),
+            self.len,
+            "data doesn't match the mapping's length"
+        );
+        unsafe { std::ptr::copy_nonoverlapping(data.as_ptr(), self.ptr, self.len) }
+    }
+}
+
+impl<'a, T: Pod> Deref for MappedBuffer<'a, T, MapRead> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+impl<'a, T: Pod> Deref for MappedBuffer<'a, T, MapReadWrite> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+impl<'a, T: Pod> DerefMut for MappedBuffer<'a, T, MapReadWrite> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+}
+
+impl<'a, T: Pod, A: MapMode> Drop for MappedBuffer<'a, T, A> {
+    fn drop(&mut self) {
+        let _ = self.unmap_inner();
+    }
+}
+
+/// Binds `buffer` to `target` and maps `len` elements of it starting at `offset` bytes.
+///
+/// `A` decides whether the mapping can be read and/or written, `access` adds the other
+/// [`MapAccess`] flags and may only repeat the read/write bits of `A`. `offset` has to be
+/// aligned for `T`.
+pub fn map_buffer_range<'a, T: Pod, A: MapMode>(
+    target: BufferKind,
+    buffer: &'a mut OwnedBuffer,
+    offset: usize,
+    len: usize,
+    access: BitFlags<MapAccess>,
+) -> Result<MappedBuffer<'a, T, A>, MapError> {
+    unsafe { map_buffer_range_raw(target, buffer.handle(), offset, len, access) }
+}
+
+/// Like [`map_buffer_range`] for a plain [`Buffer`], e.g. one from [`gen_buffer`]
+///
+/// # Safety
+///
+/// `buffer` must not be deleted, respecified, unmapped or mapped again while the returned
+/// mapping is alive, any of which leaves it pointing at memory GL may have released.
+pub unsafe fn map_buffer_range_raw<'a, T: Pod, A: MapMode>(
+    target: BufferKind,
+    buffer: Buffer,
+    offset: usize,
+    len: usize,
+    access: BitFlags<MapAccess>,
+) -> Result<MappedBuffer<'a, T, A>, MapError> {
+    let read_write = MapAccess::Read | MapAccess::Write;
+    assert!(
+        A::access().contains(access & read_write),
+        "{:?} mappings can't be made with {:?}",
+        A::access(),
+        access & read_write
+    );
+    let access = access | A::access();
+    let size = len
+        .checked_mul(std::mem::size_of::<T>())
+        .expect("mapped range is too large");
+    assert!(size > 0, "mapped range can't be empty");
+    assert!(
+        offset.is_multiple_of(std::mem::align_of::<T>()),
+        "offset {} isn't aligned for the element type",
+        offset
+    );
+
+    bind_buffer(target, buffer);
+    assert_in_bounds(target, offset, size);
+
+    let ptr = gl!(MapBufferRange(
+        target as u32,
+        offset as isize,
+        size as isize,
+        access.bits(),
+    )) as *mut T;
+    if ptr.is_null() {
+        return Err(MapError::Failed);
+    }
+    // GL aligns the start of every mapping to at least 64 bytes, so an aligned offset is enough
+    Ok(MappedBuffer {
+        target,
+        buffer,
+        ptr,
+        len,
+        access,
+        _marker: PhantomData,
+    })
+}
+
+/// `offset` is relative to the start of the mapped range, both are in bytes
+pub fn flush_mapped_buffer_range(target: BufferKind, offset: usize, length: usize) {
+    unsafe {
+        gl!(FlushMappedBufferRange(
+            target as u32,
+            offset as isize,
+            length as isize,
+        ))
+    }
 }
@@ -1,39 +1,55 @@
-use std::ptr::null_mut;
-
-pub type DebugCallbackFn = Box<dyn FnMut(DebugCallbackInfo, &str)>;
-
-#[repr(u32)]
-#[derive(Debug, Clone, Copy, PartialEq, Hash)]
-pub enum Source {
-    Api = rgl::DEBUG_SOURCE_API,
-    WindowSystem = rgl::DEBUG_SOURCE_WINDOW_SYSTEM,
-    ShaderCompiler = rgl::DEBUG_SOURCE_SHADER_COMPILER,
-    ThirdParty = rgl::DEBUG_SOURCE_THIRD_PARTY,
-    Application = rgl::DEBUG_SOURCE_APPLICATION,
-    Other = rgl::DEBUG_SOURCE_OTHER,
-}
-
-#[repr(u32)]
-#[derive(Debug, Clone, Copy, PartialEq, Hash)]
-pub enum MessageType {
-    Error = rgl::DEBUG_TYPE_ERROR,
-    DeprecatedBehavior = rgl::DEBUG_TYPE_DEPRECATED_BEHAVIOR,
-    UndefinedBehavior = rgl::DEBUG_TYPE_UNDEFINED_BEHAVIOR,
-    Portability = rgl::DEBUG_TYPE_PORTABILITY,
-    Performance = rgl::DEBUG_TYPE_PERFORMANCE,
-    Marker = rgl::DEBUG_TYPE_MARKER,
-    PushGroup = rgl::DEBUG_TYPE_PUSH_GROUP,
-    PopGroup = rgl::DEBUG_TYPE_POP_GROUP,
-    Other = rgl::DEBUG_TYPE_OTHER,
-}
-
-#[repr(u32)]
-#[derive(Debug, Clone, Copy, PartialEq, Hash)]
-pub enum Severity {
-    Notification = rgl::DEBUG_SEVERITY_NOTIFICATION,
-    Low = rgl::DEBUG_SEVERITY_LOW,
-    Medium = rgl::DEBUG_SEVERITY_MEDIUM,
-    High = rgl::DEBUG_SEVERITY_HIGH,
+use super::{
+    enable, Buffer, Capability, Framebuffer, Program, Renderbuffer, Sampler, Shader, Texture,
+    VertexArray,
+};
+use std::{
+    cell::Cell,
+    ffi::c_void,
+    ptr::{null, null_mut},
+    sync::{Condvar, Mutex, MutexGuard},
+};
+
+/// Can be called from a driver thread when [`Capability::DebugOutputSynchronous`] is disabled
+pub type DebugCallbackFn = Box<dyn FnMut(DebugCallbackInfo, &str) + Send>;
+
+/// Only ever called on the thread that issued the GL call, see [`debug_message_callback_local`]
+pub type LocalDebugCallbackFn = Box<dyn FnMut(DebugCallbackInfo, &str)>;
+
+gl_enum_open! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Source {
+        Api = rgl::DEBUG_SOURCE_API,
+        WindowSystem = rgl::DEBUG_SOURCE_WINDOW_SYSTEM,
+        ShaderCompiler = rgl::DEBUG_SOURCE_SHADER_COMPILER,
+        ThirdParty = rgl::DEBUG_SOURCE_THIRD_PARTY,
+        Application = rgl::DEBUG_SOURCE_APPLICATION,
+        Other = rgl::DEBUG_SOURCE_OTHER,
+    }
+}
+
+gl_enum_open! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum MessageType {
+        Error = rgl::DEBUG_TYPE_ERROR,
+        DeprecatedBehavior = rgl::DEBUG_TYPE_DEPRECATED_BEHAVIOR,
+        UndefinedBehavior = rgl::DEBUG_TYPE_UNDEFINED_BEHAVIOR,
+        Portability = rgl::DEBUG_TYPE_PORTABILITY,
+        Performance = rgl::DEBUG_TYPE_PERFORMANCE,
+        Marker = rgl::DEBUG_TYPE_MARKER,
+        PushGroup = rgl::DEBUG_TYPE_PUSH_GROUP,
+        PopGroup = rgl::DEBUG_TYPE_POP_GROUP,
+        Other = rgl::DEBUG_TYPE_OTHER,
+    }
+}
+
+gl_enum_open! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Severity {
+        Notification = rgl::DEBUG_SEVERITY_NOTIFICATION,
+        Low = rgl::DEBUG_SEVERITY_LOW,
+        Medium = rgl::DEBUG_SEVERITY_MEDIUM,
+        High = rgl::DEBUG_SEVERITY_HIGH,
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -44,46 +60,440 @@ pub struct DebugCallbackInfo {
     pub id: u32,
 }
 
-static mut USER_CALLBACK: Option<DebugCallbackFn> = None;
+/// Passed to the driver as `userParam`, one per registration
+struct Registration {
+    slot: Mutex<Slot>,
+    /// Notified when a message has been delivered
+    idle: Condvar,
+    /// Installed with [`debug_message_callback_local`]
+    local: bool,
+}
+
+struct Slot {
+    /// Taken out while a message is being delivered, so the callback can replace itself
+    callback: Option<LocalDebugCallbackFn>,
+    delivering: bool,
+    /// Replaced, the callback is dropped instead of put back after delivering
+    retired: bool,
+}
+
+impl Registration {
+    fn lock(&self) -> MutexGuard<'_, Slot> {
+        self.slot.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+thread_local! {
+    /// The registration this thread is delivering a message to, null if none
+    static DELIVERING: Cell<*const Registration> = const { Cell::new(null()) };
+}
 
 extern "system" fn internal_debug_callback(
     source: u32,
     ty: u32,
     id: u32,
     severity: u32,
-    _length: i32,
+    length: i32,
     message: *const i8,
-    _: *mut std::os::raw::c_void,
+    user_param: *mut c_void,
 ) {
-    if let Some(func) = unsafe { &mut USER_CALLBACK } {
-        let (source, message_type, severity): (Source, MessageType, Severity) = unsafe {
-            use std::mem::transmute;
-            (transmute(source), transmute(ty), transmute(severity)) // todo bounds checking
-        };
+    if user_param.is_null() {
+        return;
+    }
+    let registration = unsafe { &*(user_param as *const Registration) };
+
+    // GL calls made from inside the callback can report messages of their own
+    if !DELIVERING.with(Cell::get).is_null() {
+        return;
+    }
+    DELIVERING.with(|d| d.set(registration));
 
+    // messages from several driver threads are delivered one at a time
+    let mut slot = registration.lock();
+    while slot.delivering {
+        slot = registration
+            .idle
+            .wait(slot)
+            .unwrap_or_else(|e| e.into_inner());
+    }
+    let func = slot.callback.take();
+    slot.delivering = func.is_some();
+    drop(slot);
+
+    if let Some(mut func) = func {
         let info = DebugCallbackInfo {
-            source,
-            message_type,
-            severity,
+            source: source.into(),
+            message_type: ty.into(),
+            severity: severity.into(),
             id,
         };
 
-        let message = unsafe {
-            String::from_utf8(std::ffi::CStr::from_ptr(message).to_bytes().to_vec()).unwrap()
-        };
+        let message = unsafe { message_to_string(message, length) };
 
         func(info, &message);
+
+        let mut slot = registration.lock();
+        slot.delivering = false;
+        let retired = if slot.retired {
+            Some(func)
+        } else {
+            slot.callback = Some(func);
+            None
+        };
+        drop(slot);
+        registration.idle.notify_all();
+        drop(retired);
+    }
+
+    DELIVERING.with(|d| d.set(null()));
+}
+
+/// Drivers aren't required to send valid UTF-8, so invalid sequences are replaced
+unsafe fn message_to_string(message: *const i8, length: i32) -> String {
+    if message.is_null() {
+        return String::new();
     }
+    let bytes = if length >= 0 {
+        std::slice::from_raw_parts(message as *const u8, length as usize)
+    } else {
+        std::ffi::CStr::from_ptr(message).to_bytes()
+    };
+    let bytes = bytes.strip_suffix(&[0]).unwrap_or(bytes);
+    String::from_utf8_lossy(bytes).into_owned()
 }
 
+/// Installs `func` for the current context, replacing any previous callback.
+///
+/// Messages already being delivered to the previous callback on other threads finish before
+/// this returns. It may be called from inside the callback, the old one is dropped once it
+/// returns. Every replacement leaks a few bytes of bookkeeping, since a driver thread may still
+/// be about to use the old registration.
 pub fn debug_message_callback(func: Option<DebugCallbackFn>) {
+    install_callback(func.map(|func| func as LocalDebugCallbackFn), false);
+}
+
+/// Like [`debug_message_callback`] but `func` doesn't need to be `Send`.
+///
+/// This enables [`Capability::DebugOutputSynchronous`] so the driver only calls `func` on the
+/// thread that issued the GL call, disabling it again while `func` is installed panics.
+pub fn debug_message_callback_local(func: LocalDebugCallbackFn) {
+    enable(Capability::DebugOutputSynchronous);
+    install_callback(Some(func), true);
+}
+
+/// Whether the current context's callback was installed with [`debug_message_callback_local`]
+pub(crate) fn local_debug_callback_installed() -> bool {
+    current_registration().is_some_and(|registration| unsafe { (*registration).local })
+}
+
+fn install_callback(func: Option<LocalDebugCallbackFn>, local: bool) {
+    let previous = current_registration();
+
     unsafe {
-        if func.is_some() {
-            rgl::DebugMessageCallback(Some(internal_debug_callback), null_mut());
-        } else {
-            rgl::DebugMessageCallback(None, null_mut());
+        match func {
+            Some(func) => {
+                let registration = Box::new(Registration {
+                    slot: Mutex::new(Slot {
+                        callback: Some(func),
+                        delivering: false,
+                        retired: false,
+                    }),
+                    idle: Condvar::new(),
+                    local,
+                });
+                gl!(DebugMessageCallback(
+                    Some(internal_debug_callback),
+                    Box::into_raw(registration) as *mut c_void,
+                ));
+            }
+            None => gl!(DebugMessageCallback(None, null_mut())),
+        }
+    }
+
+    if let Some(previous) = previous {
+        // The registration itself is leaked since a driver thread may still be about to read the
+        // old `userParam`
+        let previous = unsafe { &*previous };
+        let mut slot = previous.lock();
+        slot.retired = true;
+        let callback = slot.callback.take();
+        // unless this thread is the one delivering, which would never finish
+        if !std::ptr::eq(DELIVERING.with(Cell::get), previous) {
+            while slot.delivering {
+                slot = previous.idle.wait(slot).unwrap_or_else(|e| e.into_inner());
+            }
         }
+        drop(slot);
+        drop(callback);
+    }
+}
+
+/// The registration installed on the current context, if it was installed by sgl
+fn current_registration() -> Option<*const Registration> {
+    let mut function: *mut c_void = null_mut();
+    let mut user_param: *mut c_void = null_mut();
+    unsafe {
+        gl!(GetPointerv(rgl::DEBUG_CALLBACK_FUNCTION, &mut function));
+        gl!(GetPointerv(rgl::DEBUG_CALLBACK_USER_PARAM, &mut user_param));
+    }
+
+    let ours = internal_debug_callback as *const c_void;
+    if std::ptr::eq(function, ours) && !user_param.is_null() {
+        Some(user_param as *const Registration)
+    } else {
+        None
+    }
+}
 
-        USER_CALLBACK = func;
+gl_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Hash)]
+    pub enum ObjectIdentifier {
+        Buffer = rgl::BUFFER,
+        Shader = rgl::SHADER,
+        Program = rgl::PROGRAM,
+        VertexArray = rgl::VERTEX_ARRAY,
+        Query = rgl::QUERY,
+        ProgramPipeline = rgl::PROGRAM_PIPELINE,
+        TransformFeedback = rgl::TRANSFORM_FEEDBACK,
+        Sampler = rgl::SAMPLER,
+        Texture = rgl::TEXTURE,
+        Renderbuffer = rgl::RENDERBUFFER,
+        Framebuffer = rgl::FRAMEBUFFER,
+    }
+}
+
+/// An sgl handle that can be named with [`object_label`]
+pub trait DebugObject {
+    const IDENTIFIER: ObjectIdentifier;
+
+    fn name(&self) -> u32;
+}
+
+impl DebugObject for Buffer {
+    const IDENTIFIER: ObjectIdentifier = ObjectIdentifier::Buffer;
+
+    fn name(&self) -> u32 {
+        **self
+    }
+}
+
+impl DebugObject for VertexArray {
+    const IDENTIFIER: ObjectIdentifier = ObjectIdentifier::VertexArray;
+
+    fn name(&self) -> u32 {
+        **self
+    }
+}
+
+impl DebugObject for Shader {
+    const IDENTIFIER: ObjectIdentifier = ObjectIdentifier::Shader;
+
+    fn name(&self) -> u32 {
+        **self
+    }
+}
+
+impl DebugObject for Program {
+    const IDENTIFIER: ObjectIdentifier = ObjectIdentifier::Program;
+
+    fn name(&self) -> u32 {
+        **self
+    }
+}
+
+impl DebugObject for Texture {
+    const IDENTIFIER: ObjectIdentifier = ObjectIdentifier::Texture;
+
+    fn name(&self) -> u32 {
+        **self
+    }
+}
+
+impl DebugObject for Sampler {
+    const IDENTIFIER: ObjectIdentifier = ObjectIdentifier::Sampler;
+
+    fn name(&self) -> u32 {
+        **self
+    }
+}
+
+impl DebugObject for Framebuffer {
+    const IDENTIFIER: ObjectIdentifier = ObjectIdentifier::Framebuffer;
+
+    fn name(&self) -> u32 {
+        **self
+    }
+}
+
+impl DebugObject for Renderbuffer {
+    const IDENTIFIER: ObjectIdentifier = ObjectIdentifier::Renderbuffer;
+
+    fn name(&self) -> u32 {
+        **self
+    }
+}
+
+/// A message read back with [`get_debug_message_log`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugMessage {
+    pub info: DebugCallbackInfo,
+    pub message: String,
+}
+
+/// Enables or disables messages matching the filter, `None` matches everything.
+///
+/// When `ids` isn't empty, `source` and `message_type` must be set and `severity` must be `None`.
+pub fn debug_message_control(
+    source: Option<Source>,
+    message_type: Option<MessageType>,
+    severity: Option<Severity>,
+    ids: &[u32],
+    enabled: bool,
+) {
+    debug_assert!(
+        ids.is_empty() || (source.is_some() && message_type.is_some() && severity.is_none())
+    );
+
+    unsafe {
+        gl!(DebugMessageControl(
+            source.map_or(rgl::DONT_CARE, u32::from),
+            message_type.map_or(rgl::DONT_CARE, u32::from),
+            severity.map_or(rgl::DONT_CARE, u32::from),
+            ids.len() as i32,
+            if ids.is_empty() { null() } else { ids.as_ptr() },
+            enabled as u8,
+        ))
+    }
+}
+
+/// `source` must be [`Source::Application`] or [`Source::ThirdParty`]
+pub fn debug_message_insert(
+    source: Source,
+    message_type: MessageType,
+    id: u32,
+    severity: Severity,
+    message: &str,
+) {
+    debug_assert!(matches!(source, Source::Application | Source::ThirdParty));
+
+    unsafe {
+        gl!(DebugMessageInsert(
+            source.into(),
+            message_type.into(),
+            id,
+            severity.into(),
+            message.len() as i32,
+            message.as_ptr() as *const i8,
+        ))
+    }
+}
+
+/// `source` must be [`Source::Application`] or [`Source::ThirdParty`], prefer [`DebugGroup`]
+pub fn push_debug_group(source: Source, id: u32, message: &str) {
+    debug_assert!(matches!(source, Source::Application | Source::ThirdParty));
+
+    unsafe {
+        gl!(PushDebugGroup(
+            source.into(),
+            id,
+            message.len() as i32,
+            message.as_ptr() as *const i8,
+        ))
+    }
+}
+
+pub fn pop_debug_group() {
+    unsafe { gl!(PopDebugGroup()) }
+}
+
+/// Pushes a debug group that is popped again when dropped
+#[must_use]
+#[derive(Debug)]
+pub struct DebugGroup(());
+
+impl DebugGroup {
+    pub fn push(source: Source, id: u32, message: &str) -> Self {
+        push_debug_group(source, id, message);
+        Self(())
+    }
+}
+
+impl Drop for DebugGroup {
+    fn drop(&mut self) {
+        pop_debug_group()
+    }
+}
+
+pub fn object_label<T: DebugObject>(object: &T, label: &str) {
+    unsafe {
+        gl!(ObjectLabel(
+            T::IDENTIFIER as u32,
+            object.name(),
+            label.len() as i32,
+            label.as_ptr() as *const i8,
+        ))
+    }
+}
+
+/// Fetches up to `count` messages from the log, only filled while no callback is installed
+pub fn get_debug_message_log(count: u32) -> Vec<DebugMessage> {
+    let mut max_length: i32 = 0;
+    unsafe { gl!(GetIntegerv(rgl::MAX_DEBUG_MESSAGE_LENGTH, &mut max_length)) };
+
+    let count = count as usize;
+    let mut sources = vec![0; count];
+    let mut types = vec![0; count];
+    let mut ids = vec![0; count];
+    let mut severities = vec![0; count];
+    let mut lengths = vec![0; count];
+    let mut log = vec![0u8; count * max_length.max(0) as usize];
+
+    let fetched = unsafe {
+        gl!(GetDebugMessageLog(
+            count as u32,
+            log.len() as i32,
+            sources.as_mut_ptr(),
+            types.as_mut_ptr(),
+            ids.as_mut_ptr(),
+            severities.as_mut_ptr(),
+            lengths.as_mut_ptr(),
+            log.as_mut_ptr() as *mut i8,
+        ))
+    } as usize;
+
+    let mut offset = 0;
+    (0..fetched)
+        .map(|i| {
+            // lengths include the null terminator
+            let end = (offset + lengths[i].max(0) as usize).min(log.len());
+            let text = &log[offset..end];
+            offset = end;
+
+            DebugMessage {
+                info: DebugCallbackInfo {
+                    source: sources[i].into(),
+                    message_type: types[i].into(),
+                    severity: severities[i].into(),
+                    id: ids[i],
+                },
+                message: String::from_utf8_lossy(text.strip_suffix(&[0]).unwrap_or(text))
+                    .into_owned(),
+            }
+        })
+        .collect()
+}
+
+/// Fetches every message currently in the log
+pub fn drain_debug_message_log() -> Vec<DebugMessage> {
+    const BATCH: u32 = 64;
+
+    let mut messages = Vec::new();
+    loop {
+        let batch = get_debug_message_log(BATCH);
+        let done = batch.len() < BATCH as usize;
+        messages.extend(batch);
+        if done {
+            return messages;
+        }
     }
 }
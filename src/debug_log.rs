@@ -0,0 +1,220 @@
+//! Ready-made [`DebugCallbackFn`]s that forward GL debug output to `log` or `tracing`
+
+use super::{DebugCallbackFn, DebugCallbackInfo, MessageType, Severity, Source};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// Drops repeats of the same message (by source, type and id) once more than `burst` of them
+/// arrive within `window`.
+///
+/// The number of dropped repeats is reported with the next repeat that gets through. Windows are
+/// forgotten once they've expired, or one window later if messages were suppressed; counts
+/// that were never reported by then are handed out by [`RateLimiter::take_expired`].
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    burst: u32,
+    window: Duration,
+    seen: HashMap<(Source, MessageType, u32), Window>,
+    last_evicted: Option<Instant>,
+    expired: Vec<(DebugCallbackInfo, u32)>,
+}
+
+#[derive(Debug, Clone)]
+struct Window {
+    start: Instant,
+    count: u32,
+    suppressed: u32,
+    /// The latest suppressed message, to report the count with if the window expires
+    info: DebugCallbackInfo,
+}
+
+impl RateLimiter {
+    pub fn new(burst: u32, window: Duration) -> Self {
+        Self {
+            burst,
+            window,
+            seen: HashMap::new(),
+            last_evicted: None,
+            expired: Vec::new(),
+        }
+    }
+
+    /// How many distinct messages are currently being tracked
+    pub fn tracked(&self) -> usize {
+        self.seen.len()
+    }
+
+    /// `None` if the message should be dropped, otherwise how many similar messages were
+    /// dropped since the last one that got through
+    pub fn check(&mut self, info: &DebugCallbackInfo) -> Option<u32> {
+        self.check_at(info, Instant::now())
+    }
+
+    /// Like [`RateLimiter::check`] for a message that arrived at `now`, which must not be
+    /// earlier than in previous calls
+    pub fn check_at(&mut self, info: &DebugCallbackInfo, now: Instant) -> Option<u32> {
+        // at most once per window so a steady stream of messages stays cheap
+        if self
+            .last_evicted
+            .is_none_or(|last| now.duration_since(last) >= self.window)
+        {
+            self.evict(now);
+        }
+
+        let window = self
+            .seen
+            .entry((info.source, info.message_type, info.id))
+            .or_insert(Window {
+                start: now,
+                count: 0,
+                suppressed: 0,
+                info: info.clone(),
+            });
+
+        if now.duration_since(window.start) >= self.window {
+            let suppressed = window.suppressed;
+            *window = Window {
+                start: now,
+                count: 1,
+                suppressed: 0,
+                info: info.clone(),
+            };
+            Some(suppressed)
+        } else if window.count < self.burst {
+            window.count += 1;
+            Some(0)
+        } else {
+            window.suppressed += 1;
+            window.info = info.clone();
+            None
+        }
+    }
+
+    fn evict(&mut self, now: Instant) {
+        let length = self.window;
+        let expired = &mut self.expired;
+        self.seen.retain(|_, window| {
+            let age = now.duration_since(window.start);
+            if age < length || (window.suppressed > 0 && age < length * 2) {
+                return true;
+            }
+            if window.suppressed > 0 {
+                expired.push((window.info.clone(), window.suppressed));
+            }
+            false
+        });
+        self.last_evicted = Some(now);
+    }
+
+    /// Suppressed counts of messages that weren't repeated again before their window was
+    /// forgotten, with the last suppressed message's info
+    pub fn take_expired(&mut self) -> Vec<(DebugCallbackInfo, u32)> {
+        std::mem::take(&mut self.expired)
+    }
+}
+
+impl Default for RateLimiter {
+    /// 5 repeats per second
+    fn default() -> Self {
+        Self::new(5, Duration::from_secs(1))
+    }
+}
+
+fn with_suppressed(message: &str, suppressed: u32) -> String {
+    if suppressed == 0 {
+        message.to_string()
+    } else {
+        format!("{} ({} similar messages suppressed)", message, suppressed)
+    }
+}
+
+fn expired_message(suppressed: u32) -> String {
+    format!("{} similar messages were suppressed", suppressed)
+}
+
+#[cfg(feature = "log")]
+fn log_level(severity: Severity) -> log::Level {
+    match severity {
+        Severity::High => log::Level::Error,
+        Severity::Medium => log::Level::Warn,
+        Severity::Low => log::Level::Info,
+        Severity::Notification => log::Level::Debug,
+        Severity::Unknown(_) => log::Level::Info,
+    }
+}
+
+/// Forwards messages to `log` under the `sgl` target, rate limited with [`RateLimiter::default`]
+#[cfg(feature = "log")]
+pub fn log_debug_callback() -> DebugCallbackFn {
+    log_debug_callback_with(RateLimiter::default())
+}
+
+#[cfg(feature = "log")]
+pub fn log_debug_callback_with(mut limiter: RateLimiter) -> DebugCallbackFn {
+    fn log(info: &DebugCallbackInfo, message: &str) {
+        log::log!(
+            target: "sgl",
+            log_level(info.severity),
+            source:? = info.source,
+            message_type:? = info.message_type,
+            id = info.id;
+            "{}",
+            message
+        );
+    }
+
+    Box::new(move |info, message| {
+        let checked = limiter.check(&info);
+        for (expired, suppressed) in limiter.take_expired() {
+            log(&expired, &expired_message(suppressed));
+        }
+        if let Some(suppressed) = checked {
+            log(&info, &with_suppressed(message, suppressed));
+        }
+    })
+}
+
+/// Forwards messages to `tracing` under the `sgl` target, rate limited with
+/// [`RateLimiter::default`]
+#[cfg(feature = "tracing")]
+pub fn tracing_debug_callback() -> DebugCallbackFn {
+    tracing_debug_callback_with(RateLimiter::default())
+}
+
+#[cfg(feature = "tracing")]
+pub fn tracing_debug_callback_with(mut limiter: RateLimiter) -> DebugCallbackFn {
+    fn trace(info: &DebugCallbackInfo, message: &str) {
+        macro_rules! event {
+            ($level:expr) => {
+                tracing::event!(
+                    target: "sgl",
+                    $level,
+                    source = ?info.source,
+                    message_type = ?info.message_type,
+                    id = info.id,
+                    "{}",
+                    message
+                )
+            };
+        }
+
+        match info.severity {
+            Severity::High => event!(tracing::Level::ERROR),
+            Severity::Medium => event!(tracing::Level::WARN),
+            Severity::Low | Severity::Unknown(_) => event!(tracing::Level::INFO),
+            Severity::Notification => event!(tracing::Level::DEBUG),
+        }
+    }
+
+    Box::new(move |info, message| {
+        let checked = limiter.check(&info);
+        for (expired, suppressed) in limiter.take_expired() {
+            trace(&expired, &expired_message(suppressed));
+        }
+        if let Some(suppressed) = checked {
+            trace(&info, &with_suppressed(message, suppressed));
+        }
+    })
+}
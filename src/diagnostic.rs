@@ -0,0 +1,193 @@
+use super::ShaderError;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl DiagnosticSeverity {
+    fn parse(word: &str) -> Option<Self> {
+        match word.to_ascii_lowercase().as_str() {
+            "error" | "fatal" => Some(Self::Error),
+            "warning" => Some(Self::Warning),
+            "info" | "note" => Some(Self::Info),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Info => "info",
+        }
+    }
+}
+
+/// A single message from a shader or program info log.
+///
+/// `line` and `column` are 1-based, and `None` when the driver didn't report them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ShaderDiagnostic {
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+}
+
+impl ShaderDiagnostic {
+    /// Renders the diagnostic rustc-style, pointing at the offending line of `source`
+    pub fn render(&self, source: &str) -> String {
+        let mut out = format!("{}: {}\n", self.severity.as_str(), self.message);
+
+        let line = match self.line {
+            Some(line) => line,
+            None => return out,
+        };
+
+        match self.column {
+            Some(column) => writeln!(out, " --> {}:{}", line, column).unwrap(),
+            None => writeln!(out, " --> {}", line).unwrap(),
+        }
+
+        let text = match source.lines().nth(line.saturating_sub(1) as usize) {
+            Some(text) if line > 0 => text,
+            _ => return out,
+        };
+
+        let gutter = " ".repeat(line.to_string().len());
+        writeln!(out, "{} |", gutter).unwrap();
+        writeln!(out, "{} | {}", line, text).unwrap();
+        match self.column {
+            Some(column) if column > 0 => {
+                let pad: String = text
+                    .chars()
+                    .take(column as usize - 1)
+                    .map(|c| if c == '\t' { '\t' } else { ' ' })
+                    .collect();
+                writeln!(out, "{} | {}^", gutter, pad).unwrap();
+            }
+            _ => {
+                let indent = text.len() - text.trim_start().len();
+                let width = text.trim().chars().count().max(1);
+                writeln!(out, "{} | {}{}", gutter, &text[..indent], "^".repeat(width)).unwrap();
+            }
+        }
+
+        out
+    }
+}
+
+/// Parses a driver info log into diagnostics.
+///
+/// Understands the Mesa (`0:12(5): error: ...`), NVIDIA (`0(12) : error C1008: ...`) and
+/// AMD/Intel (`ERROR: 0:12: ...`) formats. Lines that don't match any of them are treated as
+/// a continuation of the previous message.
+pub fn parse_info_log(log: &str) -> Vec<ShaderDiagnostic> {
+    let mut diagnostics: Vec<ShaderDiagnostic> = Vec::new();
+
+    for line in log.lines() {
+        let line = line.trim_end_matches('\0').trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(diagnostic) = parse_prefixed(line).or_else(|| parse_located(line)) {
+            diagnostics.push(diagnostic);
+        } else if let Some(last) = diagnostics.last_mut() {
+            last.message.push('\n');
+            last.message.push_str(line);
+        }
+    }
+
+    diagnostics
+}
+
+impl ShaderError {
+    pub fn diagnostics(&self) -> Vec<ShaderDiagnostic> {
+        parse_info_log(&self.log)
+    }
+
+    /// Renders every diagnostic in the log against the shader's source
+    pub fn render(&self) -> String {
+        self.diagnostics()
+            .iter()
+            .map(|d| d.render(&self.source))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn split_number(s: &str) -> Option<(u32, &str)> {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    if end == 0 {
+        return None;
+    }
+    Some((s[..end].parse().ok()?, &s[end..]))
+}
+
+/// AMD and Intel: `ERROR: 0:12: 'foo' : undeclared identifier`
+fn parse_prefixed(line: &str) -> Option<ShaderDiagnostic> {
+    let (word, rest) = line.split_once(':')?;
+    if word.chars().any(|c| c.is_ascii_lowercase()) {
+        return None;
+    }
+    let severity = DiagnosticSeverity::parse(word)?;
+    let rest = rest.trim_start();
+
+    let located = split_number(rest)
+        .and_then(|(_, rest)| rest.strip_prefix(':'))
+        .and_then(split_number)
+        .and_then(|(line, rest)| Some((line, rest.strip_prefix(':')?)));
+
+    let (line, message) = match located {
+        Some((line, message)) => (Some(line), message),
+        None => (None, rest),
+    };
+
+    Some(ShaderDiagnostic {
+        line,
+        column: None,
+        severity,
+        message: message.trim().to_string(),
+    })
+}
+
+/// Mesa: `0:12(5): error: ...` and NVIDIA: `0(12) : error C1008: ...`
+fn parse_located(line: &str) -> Option<ShaderDiagnostic> {
+    let (_file, rest) = split_number(line)?;
+
+    let (line, column, rest) = if let Some(rest) = rest.strip_prefix(':') {
+        let (line, rest) = split_number(rest)?;
+        match rest.strip_prefix('(') {
+            Some(rest) => {
+                let (column, rest) = split_number(rest)?;
+                (line, Some(column), rest.strip_prefix(')')?)
+            }
+            None => (line, None, rest),
+        }
+    } else {
+        let (line, rest) = split_number(rest.strip_prefix('(')?)?;
+        (line, None, rest.strip_prefix(')')?)
+    };
+
+    let rest = rest.trim_start().strip_prefix(':')?.trim_start();
+    let (head, message) = rest.split_once(':')?;
+    let mut words = head.split_whitespace();
+    let severity = DiagnosticSeverity::parse(words.next()?)?;
+    let message = message.trim();
+    let message = match words.next() {
+        Some(code) => format!("{}: {}", code, message),
+        None => message.to_string(),
+    };
+
+    Some(ShaderDiagnostic {
+        line: Some(line),
+        column,
+        severity,
+        message,
+    })
+}
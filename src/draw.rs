@@ -0,0 +1,343 @@
+use super::{DrawMode, Type};
+use bytemuck::{Pod, Zeroable};
+use std::ffi::c_void;
+
+gl_enum! {
+    /// The [`Type`]s GL accepts for indices
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum IndexType {
+        UnsignedByte = rgl::UNSIGNED_BYTE,
+        UnsignedShort = rgl::UNSIGNED_SHORT,
+        UnsignedInt = rgl::UNSIGNED_INT,
+    }
+}
+
+impl IndexType {
+    /// In bytes
+    pub fn size(self) -> usize {
+        match self {
+            IndexType::UnsignedByte => 1,
+            IndexType::UnsignedShort => 2,
+            IndexType::UnsignedInt => 4,
+        }
+    }
+}
+
+impl From<IndexType> for Type {
+    fn from(ty: IndexType) -> Self {
+        match ty {
+            IndexType::UnsignedByte => Type::UnsignedByte,
+            IndexType::UnsignedShort => Type::UnsignedShort,
+            IndexType::UnsignedInt => Type::UnsignedInt,
+        }
+    }
+}
+
+/// An integer that can be used as an index
+pub trait Index: Pod {
+    const TYPE: IndexType;
+}
+
+impl Index for u8 {
+    const TYPE: IndexType = IndexType::UnsignedByte;
+}
+
+impl Index for u16 {
+    const TYPE: IndexType = IndexType::UnsignedShort;
+}
+
+impl Index for u32 {
+    const TYPE: IndexType = IndexType::UnsignedInt;
+}
+
+/// One draw of [`draw_arrays_indirect`], as laid out in a `BufferKind::DrawIndirect` buffer
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct DrawArraysIndirectCommand {
+    pub count: u32,
+    pub instance_count: u32,
+    pub first: u32,
+    /// Must be 0 before GL 4.2
+    pub base_instance: u32,
+}
+
+unsafe impl Zeroable for DrawArraysIndirectCommand {}
+unsafe impl Pod for DrawArraysIndirectCommand {}
+
+/// One draw of [`draw_elements_indirect`], as laid out in a `BufferKind::DrawIndirect` buffer
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct DrawElementsIndirectCommand {
+    pub count: u32,
+    pub instance_count: u32,
+    /// In indices, not bytes
+    pub first_index: u32,
+    pub base_vertex: i32,
+    /// Must be 0 before GL 4.2
+    pub base_instance: u32,
+}
+
+unsafe impl Zeroable for DrawElementsIndirectCommand {}
+unsafe impl Pod for DrawElementsIndirectCommand {}
+
+/// A byte offset into a bound buffer, passed where GL takes a pointer
+fn offset_ptr(offset: usize) -> *const c_void {
+    offset as *const c_void
+}
+
+pub fn draw_arrays_instanced(mode: DrawMode, first: i32, count: i32, instance_count: i32) {
+    unsafe {
+        gl!(DrawArraysInstanced(
+            mode as u32,
+            first,
+            count,
+            instance_count
+        ))
+    }
+}
+
+pub fn draw_arrays_instanced_base_instance(
+    mode: DrawMode,
+    first: i32,
+    count: i32,
+    instance_count: i32,
+    base_instance: u32,
+) {
+    unsafe {
+        gl!(DrawArraysInstancedBaseInstance(
+            mode as u32,
+            first,
+            count,
+            instance_count,
+            base_instance
+        ))
+    }
+}
+
+/// Draws `count` indices starting at byte `offset` of the bound `BufferKind::ElementArray` buffer
+pub fn draw_elements(mode: DrawMode, count: i32, ty: IndexType, offset: usize) {
+    assert_index_aligned(ty, offset);
+    unsafe {
+        gl!(DrawElements(
+            mode as u32,
+            count,
+            ty as u32,
+            offset_ptr(offset)
+        ))
+    }
+}
+
+/// Draws `indices` straight from client memory.
+///
+/// # Safety
+///
+/// No buffer may be bound to `BufferKind::ElementArray`, otherwise GL reads the pointer as an
+/// offset into it. Client-side indices aren't available in core profile contexts at all.
+pub unsafe fn draw_elements_client<I: Index>(mode: DrawMode, indices: &[I]) {
+    assert!(indices.len() <= i32::MAX as usize);
+    gl!(DrawElements(
+        mode as u32,
+        indices.len() as i32,
+        I::TYPE as u32,
+        indices.as_ptr() as *const c_void
+    ))
+}
+
+/// `offset` is in bytes into the bound `BufferKind::ElementArray` buffer
+pub fn draw_elements_instanced(
+    mode: DrawMode,
+    count: i32,
+    ty: IndexType,
+    offset: usize,
+    instance_count: i32,
+) {
+    assert_index_aligned(ty, offset);
+    unsafe {
+        gl!(DrawElementsInstanced(
+            mode as u32,
+            count,
+            ty as u32,
+            offset_ptr(offset),
+            instance_count
+        ))
+    }
+}
+
+/// Like `draw_elements` with `base_vertex` added to every index before fetching vertices
+pub fn draw_elements_base_vertex(
+    mode: DrawMode,
+    count: i32,
+    ty: IndexType,
+    offset: usize,
+    base_vertex: i32,
+) {
+    assert_index_aligned(ty, offset);
+    unsafe {
+        gl!(DrawElementsBaseVertex(
+            mode as u32,
+            count,
+            ty as u32,
+            offset_ptr(offset),
+            base_vertex
+        ))
+    }
+}
+
+/// Like `draw_elements` with a promise that every index is within `start..=end`
+pub fn draw_range_elements(
+    mode: DrawMode,
+    start: u32,
+    end: u32,
+    count: i32,
+    ty: IndexType,
+    offset: usize,
+) {
+    assert!(start <= end, "index range {}..={} is empty", start, end);
+    assert_index_aligned(ty, offset);
+    unsafe {
+        gl!(DrawRangeElements(
+            mode as u32,
+            start,
+            end,
+            count,
+            ty as u32,
+            offset_ptr(offset)
+        ))
+    }
+}
+
+/// Draws `counts[i]` vertices starting at `firsts[i]` for every `i`
+pub fn multi_draw_arrays(mode: DrawMode, firsts: &[i32], counts: &[i32]) {
+    assert_eq!(
+        firsts.len(),
+        counts.len(),
+        "every draw needs both a first and a count"
+    );
+    assert!(firsts.len() < i32::MAX as usize);
+    unsafe {
+        gl!(MultiDrawArrays(
+            mode as u32,
+            firsts.as_ptr(),
+            counts.as_ptr(),
+            firsts.len() as i32
+        ))
+    }
+}
+
+/// Draws `counts[i]` indices starting at byte `offsets[i]` of the bound
+/// `BufferKind::ElementArray` buffer for every `i`
+pub fn multi_draw_elements(mode: DrawMode, counts: &[i32], ty: IndexType, offsets: &[usize]) {
+    assert_eq!(
+        counts.len(),
+        offsets.len(),
+        "every draw needs both a count and an offset"
+    );
+    assert!(counts.len() < i32::MAX as usize);
+    for offset in offsets {
+        assert_index_aligned(ty, *offset);
+    }
+    let offsets: Vec<*const c_void> = offsets.iter().map(|o| offset_ptr(*o)).collect();
+    unsafe {
+        gl!(MultiDrawElements(
+            mode as u32,
+            counts.as_ptr(),
+            ty as u32,
+            offsets.as_ptr(),
+            counts.len() as i32
+        ))
+    }
+}
+
+/// Draws with the [`DrawArraysIndirectCommand`] at byte `offset` of the bound
+/// `BufferKind::DrawIndirect` buffer
+pub fn draw_arrays_indirect(mode: DrawMode, offset: usize) {
+    assert_indirect_aligned(offset);
+    unsafe { gl!(DrawArraysIndirect(mode as u32, offset_ptr(offset))) }
+}
+
+/// Draws with the [`DrawElementsIndirectCommand`] at byte `offset` of the bound
+/// `BufferKind::DrawIndirect` buffer
+pub fn draw_elements_indirect(mode: DrawMode, ty: IndexType, offset: usize) {
+    assert_indirect_aligned(offset);
+    unsafe {
+        gl!(DrawElementsIndirect(
+            mode as u32,
+            ty as u32,
+            offset_ptr(offset)
+        ))
+    }
+}
+
+/// Draws `draw_count` [`DrawArraysIndirectCommand`]s starting at byte `offset` of the bound
+/// `BufferKind::DrawIndirect` buffer, `stride` bytes apart or tightly packed if 0
+pub fn multi_draw_arrays_indirect(mode: DrawMode, offset: usize, draw_count: i32, stride: i32) {
+    assert_indirect_aligned(offset);
+    assert_indirect_stride::<DrawArraysIndirectCommand>(stride);
+    unsafe {
+        gl!(MultiDrawArraysIndirect(
+            mode as u32,
+            offset_ptr(offset),
+            draw_count,
+            stride
+        ))
+    }
+}
+
+/// Draws `draw_count` [`DrawElementsIndirectCommand`]s starting at byte `offset` of the bound
+/// `BufferKind::DrawIndirect` buffer, `stride` bytes apart or tightly packed if 0
+pub fn multi_draw_elements_indirect(
+    mode: DrawMode,
+    ty: IndexType,
+    offset: usize,
+    draw_count: i32,
+    stride: i32,
+) {
+    assert_indirect_aligned(offset);
+    assert_indirect_stride::<DrawElementsIndirectCommand>(stride);
+    unsafe {
+        gl!(MultiDrawElementsIndirect(
+            mode as u32,
+            ty as u32,
+            offset_ptr(offset),
+            draw_count,
+            stride
+        ))
+    }
+}
+
+/// Indirect commands are made of `GLuint`s, GL rejects anything not aligned to one
+fn assert_indirect_aligned(value: usize) {
+    assert!(
+        value.is_multiple_of(4),
+        "indirect draw offset and stride must be multiples of 4, got {}",
+        value
+    );
+}
+
+/// Panics unless `stride` is 0 or an aligned step of at least one `C`, anything shorter would make
+/// consecutive commands overlap
+fn assert_indirect_stride<C>(stride: i32) {
+    assert!(
+        stride >= 0,
+        "indirect draw stride can't be negative, got {}",
+        stride
+    );
+    let stride = stride as usize;
+    assert!(
+        stride == 0 || stride >= std::mem::size_of::<C>(),
+        "indirect draw stride must be 0 or at least {} bytes, got {}",
+        std::mem::size_of::<C>(),
+        stride
+    );
+    assert_indirect_aligned(stride);
+}
+
+/// GL needs index offsets to be aligned to the index size
+fn assert_index_aligned(ty: IndexType, offset: usize) {
+    assert!(
+        offset.is_multiple_of(ty.size()),
+        "offset {} isn't aligned to {:?} indices",
+        offset,
+        ty
+    );
+}
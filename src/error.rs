@@ -0,0 +1,93 @@
+/// Upper bound on how many errors are drained at once, `GL_CONTEXT_LOST` can be reported forever
+const MAX_DRAINED_ERRORS: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GlError {
+    InvalidEnum,
+    InvalidValue,
+    InvalidOperation,
+    StackOverflow,
+    StackUnderflow,
+    OutOfMemory,
+    InvalidFramebufferOperation,
+    ContextLost,
+    Unknown(u32),
+}
+
+impl GlError {
+    /// `None` for `GL_NO_ERROR`
+    pub fn from_raw(raw: u32) -> Option<Self> {
+        Some(match raw {
+            rgl::NO_ERROR => return None,
+            rgl::INVALID_ENUM => Self::InvalidEnum,
+            rgl::INVALID_VALUE => Self::InvalidValue,
+            rgl::INVALID_OPERATION => Self::InvalidOperation,
+            rgl::STACK_OVERFLOW => Self::StackOverflow,
+            rgl::STACK_UNDERFLOW => Self::StackUnderflow,
+            rgl::OUT_OF_MEMORY => Self::OutOfMemory,
+            rgl::INVALID_FRAMEBUFFER_OPERATION => Self::InvalidFramebufferOperation,
+            rgl::CONTEXT_LOST => Self::ContextLost,
+            other => Self::Unknown(other),
+        })
+    }
+}
+
+impl std::fmt::Display for GlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidEnum => write!(f, "GL_INVALID_ENUM"),
+            Self::InvalidValue => write!(f, "GL_INVALID_VALUE"),
+            Self::InvalidOperation => write!(f, "GL_INVALID_OPERATION"),
+            Self::StackOverflow => write!(f, "GL_STACK_OVERFLOW"),
+            Self::StackUnderflow => write!(f, "GL_STACK_UNDERFLOW"),
+            Self::OutOfMemory => write!(f, "GL_OUT_OF_MEMORY"),
+            Self::InvalidFramebufferOperation => write!(f, "GL_INVALID_FRAMEBUFFER_OPERATION"),
+            Self::ContextLost => write!(f, "GL_CONTEXT_LOST"),
+            Self::Unknown(raw) => write!(f, "unknown GL error 0x{:X}", raw),
+        }
+    }
+}
+
+impl std::error::Error for GlError {}
+
+/// Pops a single error off the queue
+pub fn get_error() -> Option<GlError> {
+    GlError::from_raw(unsafe { rgl::GetError() })
+}
+
+/// Drains the error queue, returning every error that was recorded
+pub fn check_errors() -> Result<(), Vec<GlError>> {
+    let errors: Vec<GlError> = std::iter::from_fn(get_error)
+        .take(MAX_DRAINED_ERRORS)
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Called after every wrapped GL call when the `checked` feature is enabled
+///
+/// The panic names the GL entry point, its location is inside sgl rather than the caller.
+#[doc(hidden)]
+pub fn assert_no_errors(function: &str) {
+    if let Err(errors) = check_errors() {
+        panic!("{} failed: {:?}", function, errors);
+    }
+}
+
+/// Returned when converting a raw value that doesn't match any variant of an sgl enum
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UnknownEnumValue {
+    pub name: &'static str,
+    pub value: u32,
+}
+
+impl std::fmt::Display for UnknownEnumValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "0x{:X} is not a valid {}", self.value, self.name)
+    }
+}
+
+impl std::error::Error for UnknownEnumValue {}
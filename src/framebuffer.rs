@@ -0,0 +1,622 @@
+use super::{InternalFormat, Texture};
+use shrinkwraprs::Shrinkwrap;
+
+/// [`Framebuffer::NONE`] is the default framebuffer of the window
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Shrinkwrap)]
+pub struct Framebuffer(u32);
+
+impl Framebuffer {
+    pub const NONE: Self = Self(0);
+}
+
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Shrinkwrap)]
+pub struct Renderbuffer(u32);
+
+impl Renderbuffer {
+    pub const NONE: Self = Self(0);
+}
+
+/// A [`Framebuffer`] that is deleted when dropped
+#[derive(Debug, PartialEq, Eq, Hash, Shrinkwrap)]
+pub struct OwnedFramebuffer(Framebuffer);
+
+impl OwnedFramebuffer {
+    pub fn new() -> Self {
+        Self(gen_framebuffer())
+    }
+
+    /// Takes ownership of `framebuffer`, it will be deleted when the returned value is dropped
+    pub fn from_raw(framebuffer: Framebuffer) -> Self {
+        Self(framebuffer)
+    }
+
+    pub fn handle(&self) -> Framebuffer {
+        self.0
+    }
+
+    /// Releases ownership without deleting, the caller is responsible for [`delete_framebuffer`]
+    pub fn into_raw(self) -> Framebuffer {
+        let framebuffer = self.0;
+        std::mem::forget(self);
+        framebuffer
+    }
+}
+
+impl Default for OwnedFramebuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for OwnedFramebuffer {
+    fn drop(&mut self) {
+        delete_framebuffer(self.0)
+    }
+}
+
+/// A [`Renderbuffer`] that is deleted when dropped
+#[derive(Debug, PartialEq, Eq, Hash, Shrinkwrap)]
+pub struct OwnedRenderbuffer(Renderbuffer);
+
+impl OwnedRenderbuffer {
+    pub fn new() -> Self {
+        Self(gen_renderbuffer())
+    }
+
+    /// Takes ownership of `renderbuffer`, it will be deleted when the returned value is dropped
+    pub fn from_raw(renderbuffer: Renderbuffer) -> Self {
+        Self(renderbuffer)
+    }
+
+    pub fn handle(&self) -> Renderbuffer {
+        self.0
+    }
+
+    /// Releases ownership without deleting, the caller is responsible for [`delete_renderbuffer`]
+    pub fn into_raw(self) -> Renderbuffer {
+        let renderbuffer = self.0;
+        std::mem::forget(self);
+        renderbuffer
+    }
+}
+
+impl Default for OwnedRenderbuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for OwnedRenderbuffer {
+    fn drop(&mut self) {
+        delete_renderbuffer(self.0)
+    }
+}
+
+gl_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum FramebufferTarget {
+        /// Both `Draw` and `Read`
+        Framebuffer = rgl::FRAMEBUFFER,
+        Draw = rgl::DRAW_FRAMEBUFFER,
+        Read = rgl::READ_FRAMEBUFFER,
+    }
+}
+
+gl_enum! {
+    /// The 2D images [`framebuffer_texture_2d`] can attach
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum FramebufferTexture2DTarget {
+        Texture2D = rgl::TEXTURE_2D,
+        TextureRectangle = rgl::TEXTURE_RECTANGLE,
+        CubeMapPositiveX = rgl::TEXTURE_CUBE_MAP_POSITIVE_X,
+        CubeMapNegativeX = rgl::TEXTURE_CUBE_MAP_NEGATIVE_X,
+        CubeMapPositiveY = rgl::TEXTURE_CUBE_MAP_POSITIVE_Y,
+        CubeMapNegativeY = rgl::TEXTURE_CUBE_MAP_NEGATIVE_Y,
+        CubeMapPositiveZ = rgl::TEXTURE_CUBE_MAP_POSITIVE_Z,
+        CubeMapNegativeZ = rgl::TEXTURE_CUBE_MAP_NEGATIVE_Z,
+        Texture2DMultisample = rgl::TEXTURE_2D_MULTISAMPLE,
+    }
+}
+
+/// A slot of a framebuffer that an image can be attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Attachment {
+    /// `GL_COLOR_ATTACHMENTi`
+    Color(u32),
+    Depth,
+    Stencil,
+    DepthStencil,
+}
+
+impl Attachment {
+    pub fn raw(self) -> u32 {
+        match self {
+            Attachment::Color(i) => rgl::COLOR_ATTACHMENT0 + i,
+            Attachment::Depth => rgl::DEPTH_ATTACHMENT,
+            Attachment::Stencil => rgl::STENCIL_ATTACHMENT,
+            Attachment::DepthStencil => rgl::DEPTH_STENCIL_ATTACHMENT,
+        }
+    }
+}
+
+/// A color buffer fragment outputs can be written to or pixels read from. The `Front`/`Back`
+/// variants only exist in the default framebuffer, `Color` only in framebuffer objects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DrawBuffer {
+    None,
+    /// `GL_COLOR_ATTACHMENTi`
+    Color(u32),
+    FrontLeft,
+    FrontRight,
+    BackLeft,
+    BackRight,
+    Front,
+    Back,
+}
+
+impl DrawBuffer {
+    pub fn raw(self) -> u32 {
+        match self {
+            DrawBuffer::None => rgl::NONE,
+            DrawBuffer::Color(i) => rgl::COLOR_ATTACHMENT0 + i,
+            DrawBuffer::FrontLeft => rgl::FRONT_LEFT,
+            DrawBuffer::FrontRight => rgl::FRONT_RIGHT,
+            DrawBuffer::BackLeft => rgl::BACK_LEFT,
+            DrawBuffer::BackRight => rgl::BACK_RIGHT,
+            DrawBuffer::Front => rgl::FRONT,
+            DrawBuffer::Back => rgl::BACK,
+        }
+    }
+}
+
+gl_enum_open! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum FramebufferStatus {
+        Complete = rgl::FRAMEBUFFER_COMPLETE,
+        Undefined = rgl::FRAMEBUFFER_UNDEFINED,
+        IncompleteAttachment = rgl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT,
+        IncompleteMissingAttachment = rgl::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT,
+        IncompleteDrawBuffer = rgl::FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER,
+        IncompleteReadBuffer = rgl::FRAMEBUFFER_INCOMPLETE_READ_BUFFER,
+        Unsupported = rgl::FRAMEBUFFER_UNSUPPORTED,
+        IncompleteMultisample = rgl::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE,
+        IncompleteLayerTargets = rgl::FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS,
+    }
+}
+
+impl FramebufferStatus {
+    pub fn is_complete(self) -> bool {
+        self == FramebufferStatus::Complete
+    }
+}
+
+impl std::fmt::Display for FramebufferStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FramebufferStatus::Complete => write!(f, "framebuffer is complete"),
+            FramebufferStatus::Undefined => write!(
+                f,
+                "the default framebuffer is bound but the window has none"
+            ),
+            FramebufferStatus::IncompleteAttachment => write!(
+                f,
+                "an attachment has no storage, a zero size or a format that can't be rendered \
+                 to in that slot"
+            ),
+            FramebufferStatus::IncompleteMissingAttachment => {
+                write!(f, "no images are attached")
+            }
+            FramebufferStatus::IncompleteDrawBuffer => {
+                write!(
+                    f,
+                    "a draw buffer names a color attachment with nothing attached"
+                )
+            }
+            FramebufferStatus::IncompleteReadBuffer => {
+                write!(
+                    f,
+                    "the read buffer names a color attachment with nothing attached"
+                )
+            }
+            FramebufferStatus::Unsupported => write!(
+                f,
+                "the driver doesn't support this combination of attachment formats"
+            ),
+            FramebufferStatus::IncompleteMultisample => write!(
+                f,
+                "attachments have different sample counts or fixed sample locations"
+            ),
+            FramebufferStatus::IncompleteLayerTargets => write!(
+                f,
+                "some attachments are layered and others aren't, or they're of different targets"
+            ),
+            FramebufferStatus::Unknown(value) => {
+                write!(f, "unknown framebuffer status 0x{:04X}", value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FramebufferStatus {}
+
+pub fn gen_framebuffers<const N: usize>() -> [Framebuffer; N] {
+    assert!(N < u32::MAX as usize);
+    let mut framebuffers = [Framebuffer(0); N];
+    unsafe {
+        gl!(GenFramebuffers(
+            N as i32,
+            framebuffers.as_mut_ptr() as *mut u32
+        ))
+    };
+    framebuffers
+}
+
+pub fn gen_framebuffer() -> Framebuffer {
+    gen_framebuffers::<1>()[0]
+}
+
+/// Deletes every framebuffer in `framebuffers`, skipping [`Framebuffer::NONE`]
+pub fn delete_framebuffers(framebuffers: &[Framebuffer]) {
+    let names: Vec<u32> = framebuffers
+        .iter()
+        .filter(|f| **f != Framebuffer::NONE)
+        .map(|f| f.0)
+        .collect();
+    if names.is_empty() {
+        return;
+    }
+    assert!(names.len() < i32::MAX as usize);
+    unsafe { gl!(DeleteFramebuffers(names.len() as i32, names.as_ptr())) }
+}
+
+pub fn delete_framebuffer(framebuffer: Framebuffer) {
+    delete_framebuffers(&[framebuffer])
+}
+
+pub fn bind_framebuffer(target: FramebufferTarget, framebuffer: Framebuffer) {
+    unsafe { gl!(BindFramebuffer(target as u32, framebuffer.0)) }
+}
+
+pub fn gen_renderbuffers<const N: usize>() -> [Renderbuffer; N] {
+    assert!(N < u32::MAX as usize);
+    let mut renderbuffers = [Renderbuffer(0); N];
+    unsafe {
+        gl!(GenRenderbuffers(
+            N as i32,
+            renderbuffers.as_mut_ptr() as *mut u32
+        ))
+    };
+    renderbuffers
+}
+
+pub fn gen_renderbuffer() -> Renderbuffer {
+    gen_renderbuffers::<1>()[0]
+}
+
+/// Deletes every renderbuffer in `renderbuffers`, skipping [`Renderbuffer::NONE`]
+pub fn delete_renderbuffers(renderbuffers: &[Renderbuffer]) {
+    let names: Vec<u32> = renderbuffers
+        .iter()
+        .filter(|r| **r != Renderbuffer::NONE)
+        .map(|r| r.0)
+        .collect();
+    if names.is_empty() {
+        return;
+    }
+    assert!(names.len() < i32::MAX as usize);
+    unsafe { gl!(DeleteRenderbuffers(names.len() as i32, names.as_ptr())) }
+}
+
+pub fn delete_renderbuffer(renderbuffer: Renderbuffer) {
+    delete_renderbuffers(&[renderbuffer])
+}
+
+pub fn bind_renderbuffer(renderbuffer: Renderbuffer) {
+    unsafe { gl!(BindRenderbuffer(rgl::RENDERBUFFER, renderbuffer.0)) }
+}
+
+/// Allocates storage for the bound renderbuffer
+pub fn renderbuffer_storage(format: InternalFormat, width: i32, height: i32) {
+    assert!(
+        width > 0 && height > 0,
+        "renderbuffer storage can't be empty"
+    );
+    unsafe {
+        gl!(RenderbufferStorage(
+            rgl::RENDERBUFFER,
+            format as u32,
+            width,
+            height
+        ))
+    }
+}
+
+/// Like [`renderbuffer_storage`] with `samples` samples per pixel, 0 isn't multisampled
+pub fn renderbuffer_storage_multisample(
+    samples: i32,
+    format: InternalFormat,
+    width: i32,
+    height: i32,
+) {
+    assert!(samples >= 0, "sample count can't be negative");
+    assert!(
+        width > 0 && height > 0,
+        "renderbuffer storage can't be empty"
+    );
+    unsafe {
+        gl!(RenderbufferStorageMultisample(
+            rgl::RENDERBUFFER,
+            samples,
+            format as u32,
+            width,
+            height
+        ))
+    }
+}
+
+/// Attaches mip `level` of `texture` to the framebuffer bound to `target`, all layers at once
+/// for array, cube map and 3D textures
+pub fn framebuffer_texture(
+    target: FramebufferTarget,
+    attachment: Attachment,
+    texture: Texture,
+    level: i32,
+) {
+    unsafe {
+        gl!(FramebufferTexture(
+            target as u32,
+            attachment.raw(),
+            *texture,
+            level
+        ))
+    }
+}
+
+/// Attaches mip `level` of a 2D image of `texture`, `texture_target` picks the cube map face.
+/// Multisample textures only have level 0.
+pub fn framebuffer_texture_2d(
+    target: FramebufferTarget,
+    attachment: Attachment,
+    texture_target: FramebufferTexture2DTarget,
+    texture: Texture,
+    level: i32,
+) {
+    unsafe {
+        gl!(FramebufferTexture2D(
+            target as u32,
+            attachment.raw(),
+            texture_target as u32,
+            *texture,
+            level
+        ))
+    }
+}
+
+/// Attaches a single `layer` of mip `level` of an array, cube map or 3D `texture`
+pub fn framebuffer_texture_layer(
+    target: FramebufferTarget,
+    attachment: Attachment,
+    texture: Texture,
+    level: i32,
+    layer: i32,
+) {
+    unsafe {
+        gl!(FramebufferTextureLayer(
+            target as u32,
+            attachment.raw(),
+            *texture,
+            level,
+            layer
+        ))
+    }
+}
+
+pub fn framebuffer_renderbuffer(
+    target: FramebufferTarget,
+    attachment: Attachment,
+    renderbuffer: Renderbuffer,
+) {
+    unsafe {
+        gl!(FramebufferRenderbuffer(
+            target as u32,
+            attachment.raw(),
+            rgl::RENDERBUFFER,
+            renderbuffer.0
+        ))
+    }
+}
+
+/// Routes fragment shader output `i` to `buffers[i]` of the bound draw framebuffer
+pub fn draw_buffers(buffers: &[DrawBuffer]) {
+    assert!(buffers.len() < i32::MAX as usize);
+    let buffers: Vec<u32> = buffers.iter().map(|b| b.raw()).collect();
+    unsafe { gl!(DrawBuffers(buffers.len() as i32, buffers.as_ptr())) }
+}
+
+/// Selects the color buffer of the bound read framebuffer that `read_pixels` and blits read from
+pub fn read_buffer(buffer: DrawBuffer) {
+    unsafe { gl!(ReadBuffer(buffer.raw())) }
+}
+
+pub fn check_framebuffer_status(target: FramebufferTarget) -> FramebufferStatus {
+    FramebufferStatus::from(unsafe { gl!(CheckFramebufferStatus(target as u32)) })
+}
+
+/// What [`FramebufferBuilder`] attaches to a slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AttachmentDesc {
+    /// All layers of mip `level`
+    Texture {
+        texture: Texture,
+        level: i32,
+    },
+    /// A single layer of mip `level` of an array, cube map or 3D texture
+    TextureLayer {
+        texture: Texture,
+        level: i32,
+        layer: i32,
+    },
+    Renderbuffer(Renderbuffer),
+    /// A renderbuffer the size of the framebuffer allocated and owned by the builder's result,
+    /// `samples` of 0 isn't multisampled
+    NewRenderbuffer {
+        format: InternalFormat,
+        samples: i32,
+    },
+}
+
+/// A framebuffer along with the renderbuffers [`FramebufferBuilder`] allocated for it
+#[derive(Debug)]
+pub struct FramebufferObject {
+    framebuffer: OwnedFramebuffer,
+    renderbuffers: Vec<(Attachment, OwnedRenderbuffer)>,
+}
+
+impl FramebufferObject {
+    pub fn framebuffer(&self) -> Framebuffer {
+        self.framebuffer.handle()
+    }
+
+    /// The renderbuffer allocated for `attachment` by [`AttachmentDesc::NewRenderbuffer`]
+    pub fn renderbuffer(&self, attachment: Attachment) -> Option<Renderbuffer> {
+        self.renderbuffers
+            .iter()
+            .find(|(a, _)| *a == attachment)
+            .map(|(_, r)| r.handle())
+    }
+
+    pub fn bind(&self, target: FramebufferTarget) {
+        bind_framebuffer(target, self.framebuffer())
+    }
+}
+
+/// Assembles a framebuffer from attachment descriptions and checks that it's complete.
+///
+/// Unless set explicitly, fragment output `i` is drawn to color attachment `i` and the read buffer
+/// is the lowest color attachment.
+#[derive(Debug, Clone)]
+pub struct FramebufferBuilder {
+    width: i32,
+    height: i32,
+    attachments: Vec<(Attachment, AttachmentDesc)>,
+    draw_buffers: Option<Vec<DrawBuffer>>,
+    read_buffer: Option<DrawBuffer>,
+}
+
+impl FramebufferBuilder {
+    /// `width` and `height` are only used for [`AttachmentDesc::NewRenderbuffer`]
+    pub fn new(width: i32, height: i32) -> Self {
+        Self {
+            width,
+            height,
+            attachments: Vec::new(),
+            draw_buffers: None,
+            read_buffer: None,
+        }
+    }
+
+    /// Replaces whatever was attached to `attachment` before
+    pub fn attach(mut self, attachment: Attachment, desc: AttachmentDesc) -> Self {
+        self.attachments.retain(|(a, _)| *a != attachment);
+        self.attachments.push((attachment, desc));
+        self
+    }
+
+    pub fn color(self, index: u32, desc: AttachmentDesc) -> Self {
+        self.attach(Attachment::Color(index), desc)
+    }
+
+    pub fn depth(self, desc: AttachmentDesc) -> Self {
+        self.attach(Attachment::Depth, desc)
+    }
+
+    pub fn stencil(self, desc: AttachmentDesc) -> Self {
+        self.attach(Attachment::Stencil, desc)
+    }
+
+    pub fn depth_stencil(self, desc: AttachmentDesc) -> Self {
+        self.attach(Attachment::DepthStencil, desc)
+    }
+
+    pub fn draw_buffers(mut self, buffers: &[DrawBuffer]) -> Self {
+        self.draw_buffers = Some(buffers.to_vec());
+        self
+    }
+
+    pub fn read_buffer(mut self, buffer: DrawBuffer) -> Self {
+        self.read_buffer = Some(buffer);
+        self
+    }
+
+    /// Creates the framebuffer and leaves it bound to `FramebufferTarget::Framebuffer`.
+    ///
+    /// If it isn't complete everything created here is deleted again and the status says why.
+    pub fn build(self) -> Result<FramebufferObject, FramebufferStatus> {
+        let framebuffer = OwnedFramebuffer::new();
+        bind_framebuffer(FramebufferTarget::Framebuffer, *framebuffer);
+
+        let target = FramebufferTarget::Framebuffer;
+        let mut renderbuffers = Vec::new();
+        for (attachment, desc) in &self.attachments {
+            match *desc {
+                AttachmentDesc::Texture { texture, level } => {
+                    framebuffer_texture(target, *attachment, texture, level)
+                }
+                AttachmentDesc::TextureLayer {
+                    texture,
+                    level,
+                    layer,
+                } => framebuffer_texture_layer(target, *attachment, texture, level, layer),
+                AttachmentDesc::Renderbuffer(renderbuffer) => {
+                    framebuffer_renderbuffer(target, *attachment, renderbuffer)
+                }
+                AttachmentDesc::NewRenderbuffer { format, samples } => {
+                    let renderbuffer = OwnedRenderbuffer::new();
+                    bind_renderbuffer(*renderbuffer);
+                    renderbuffer_storage_multisample(samples, format, self.width, self.height);
+                    framebuffer_renderbuffer(target, *attachment, *renderbuffer);
+                    renderbuffers.push((*attachment, renderbuffer));
+                }
+            }
+        }
+
+        let mut colors: Vec<u32> = self
+            .attachments
+            .iter()
+            .filter_map(|(a, _)| match a {
+                Attachment::Color(i) => Some(*i),
+                _ => None,
+            })
+            .collect();
+        colors.sort_unstable();
+
+        // indexed by output location, so locations without an attachment are discarded
+        let draw = self.draw_buffers.unwrap_or_else(|| {
+            let mut draw = vec![DrawBuffer::None; colors.last().map_or(1, |i| *i as usize + 1)];
+            for i in &colors {
+                draw[*i as usize] = DrawBuffer::Color(*i);
+            }
+            draw
+        });
+        draw_buffers(&draw);
+        let read = self.read_buffer.unwrap_or_else(|| {
+            colors
+                .first()
+                .map_or(DrawBuffer::None, |i| DrawBuffer::Color(*i))
+        });
+        read_buffer(read);
+
+        match check_framebuffer_status(target) {
+            FramebufferStatus::Complete => Ok(FramebufferObject {
+                framebuffer,
+                renderbuffers,
+            }),
+            status => {
+                bind_framebuffer(target, Framebuffer::NONE);
+                Err(status)
+            }
+        }
+    }
+}
@@ -2,64 +2,167 @@
 
 pub extern crate gl as rgl;
 
-use std::{
-    ffi::c_void, ptr::null,
-};
 use enumflags2::{bitflags, BitFlags};
 
+/// Calls `rgl::$f`, asserting that it raised no GL error when the `checked` feature is enabled
+macro_rules! gl {
+    ($f:ident($($arg:expr),* $(,)?)) => {{
+        let result = $crate::rgl::$f($($arg),*);
+        #[cfg(feature = "checked")]
+        $crate::assert_no_errors(concat!("gl", stringify!($f)));
+        result
+    }};
+}
+
+/// Declares a `#[repr(u32)]` enum of GL constants along with its `TryFrom<u32>` impl
+macro_rules! gl_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident = $value:expr),* $(,)?
+        }
+    ) => {
+        #[repr(u32)]
+        $(#[$meta])*
+        $vis enum $name {
+            $($(#[$variant_meta])* $variant = $value),*
+        }
+
+        // deprecating an enum or variant shouldn't warn about its own conversion
+        #[allow(deprecated)]
+        impl std::convert::TryFrom<u32> for $name {
+            type Error = $crate::UnknownEnumValue;
+
+            fn try_from(value: u32) -> Result<Self, Self::Error> {
+                $(if value == $value {
+                    return Ok(Self::$variant);
+                })*
+                Err($crate::UnknownEnumValue {
+                    name: stringify!($name),
+                    value,
+                })
+            }
+        }
+    };
+}
+
+/// Like [`gl_enum`] but for values that drivers may extend, anything unrecognised is kept in
+/// an `Unknown` variant instead of failing the conversion
+macro_rules! gl_enum_open {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident = $value:expr),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $($(#[$variant_meta])* $variant,)*
+            Unknown(u32),
+        }
+
+        impl From<u32> for $name {
+            fn from(value: u32) -> Self {
+                $(if value == $value {
+                    return Self::$variant;
+                })*
+                Self::Unknown(value)
+            }
+        }
+
+        impl From<$name> for u32 {
+            fn from(value: $name) -> Self {
+                match value {
+                    $($name::$variant => $value,)*
+                    $name::Unknown(value) => value,
+                }
+            }
+        }
+    };
+}
+
+mod error;
 mod debug;
+#[cfg(any(feature = "log", feature = "tracing"))]
+mod debug_log;
 mod caps;
 mod buffer;
+mod stream;
+mod sync;
+mod vertex;
 mod program;
+mod diagnostic;
+mod draw;
+mod packed;
 mod texture;
+mod sampling;
+mod sampler;
+mod framebuffer;
 
 pub use rgl::load_with;
+pub use bytemuck::Pod;
+pub use error::*;
 pub use debug::*;
+#[cfg(any(feature = "log", feature = "tracing"))]
+pub use debug_log::*;
 pub use caps::*;
 pub use buffer::*;
+pub use stream::*;
+pub use sync::*;
+pub use vertex::*;
 pub use program::*;
+pub use diagnostic::*;
+pub use draw::*;
+pub use packed::*;
 pub use texture::*;
+pub use sampling::*;
+pub use sampler::*;
+pub use framebuffer::*;
 
-#[repr(u32)]
-#[derive(Debug, Clone, Copy, PartialEq, Hash)]
-pub enum Usage {
-    StreamDraw = rgl::STREAM_DRAW,
-    StreamRead = rgl::STREAM_READ,
-    StreamCopy = rgl::STREAM_COPY,
-    StaticDraw = rgl::STATIC_DRAW,
-    StaticRead = rgl::STATIC_READ,
-    StaticCopy = rgl::STATIC_COPY,
-    DynamicDraw = rgl::DYNAMIC_DRAW,
-    DynamicRead = rgl::DYNAMIC_READ,
-    DynamicCopy = rgl::DYNAMIC_COPY,
+gl_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Hash)]
+    pub enum Usage {
+        StreamDraw = rgl::STREAM_DRAW,
+        StreamRead = rgl::STREAM_READ,
+        StreamCopy = rgl::STREAM_COPY,
+        StaticDraw = rgl::STATIC_DRAW,
+        StaticRead = rgl::STATIC_READ,
+        StaticCopy = rgl::STATIC_COPY,
+        DynamicDraw = rgl::DYNAMIC_DRAW,
+        DynamicRead = rgl::DYNAMIC_READ,
+        DynamicCopy = rgl::DYNAMIC_COPY,
+    }
 }
 
-#[repr(u32)]
-#[derive(Debug, Clone, Copy, PartialEq, Hash)]
-pub enum Type {
-    // These first few are accepted by both `vertex_attrib_pointer` and `vertex_attrib_ipointer`
-    Byte = rgl::BYTE,                    // i8
-    UnsignedByte = rgl::UNSIGNED_BYTE,   // u8
-    Short = rgl::SHORT,                  // i16
-    UnsignedShort = rgl::UNSIGNED_SHORT, // u16
-    Int = rgl::INT,                      // i32
-    UnsignedInt = rgl::UNSIGNED_INT,     // u32
-
-    // These next few are accepted by `vertex_attrib_pointer` only
-    HalfFloat = rgl::HALF_FLOAT, // f16
-    Float = rgl::FLOAT,          // f32
-    Double = rgl::DOUBLE,        // f64
-    Fixed = rgl::FIXED,          // TODO: ?
-    #[allow(non_camel_case_types)]
-    Int_2_10_10_10_Rev = rgl::INT_2_10_10_10_REV, // TODO: ?
-    #[allow(non_camel_case_types)]
-    UnsignedInt_2_10_10_10_Rev = rgl::UNSIGNED_INT_2_10_10_10_REV, // TODO: ?
-    #[allow(non_camel_case_types)]
-    UnsignedInt_10F_11F_11F_Rev = rgl::UNSIGNED_INT_10F_11F_11F_REV, // TODO: ?
+gl_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Hash)]
+    pub enum Type {
+        // These first few are accepted by both `vertex_attrib_pointer` and `vertex_attrib_ipointer`
+        Byte = rgl::BYTE,                    // i8
+        UnsignedByte = rgl::UNSIGNED_BYTE,   // u8
+        Short = rgl::SHORT,                  // i16
+        UnsignedShort = rgl::UNSIGNED_SHORT, // u16
+        Int = rgl::INT,                      // i32
+        UnsignedInt = rgl::UNSIGNED_INT,     // u32
+
+        // These next few are accepted by `vertex_attrib_pointer` only
+        HalfFloat = rgl::HALF_FLOAT, // f16
+        Float = rgl::FLOAT,          // f32
+        Double = rgl::DOUBLE,        // f64
+        Fixed = rgl::FIXED,          // i32, 16.16 fixed point
+
+        // a whole attribute packed into one u32, see `pack_snorm_2_10_10_10_rev` and friends
+        #[allow(non_camel_case_types)]
+        Int_2_10_10_10_Rev = rgl::INT_2_10_10_10_REV, // signed 10 bit xyz, 2 bit w
+        #[allow(non_camel_case_types)]
+        UnsignedInt_2_10_10_10_Rev = rgl::UNSIGNED_INT_2_10_10_10_REV, // unsigned 10 bit xyz, 2 bit w
+        #[allow(non_camel_case_types)]
+        UnsignedInt_10F_11F_11F_Rev = rgl::UNSIGNED_INT_10F_11F_11F_REV, // 11, 11 and 10 bit floats
+    }
 }
 
 impl Type {
-    // in bytes
+    /// In bytes, packed types are the size of the whole attribute rather than one component
     pub fn size(self) -> usize {
         use std::mem::size_of;
         match self {
@@ -69,42 +172,86 @@ impl Type {
             Type::UnsignedShort => size_of::<u16>(),
             Type::Int => size_of::<i32>(),
             Type::UnsignedInt => size_of::<u32>(),
-            Type::HalfFloat => size_of::<f32>() / 2,
+            Type::HalfFloat => 2,
             Type::Float => size_of::<f32>(),
             Type::Double => size_of::<f64>(),
-            // Type::Fixed => {}
-            // Type::Int_2_10_10_10_Rev => {}
-            // Type::UnsignedInt_2_10_10_10_Rev => {}
-            // Type::UnsignedInt_10F_11F_11F_Rev => {}
-            _ => todo!(),
+            // 16.16 fixed point
+            Type::Fixed => size_of::<i32>(),
+            Type::Int_2_10_10_10_Rev
+            | Type::UnsignedInt_2_10_10_10_Rev
+            | Type::UnsignedInt_10F_11F_11F_Rev => size_of::<u32>(),
+        }
+    }
+
+    /// Whether the shader can read it as `int`/`uint`
+    pub fn is_integer(self) -> bool {
+        matches!(
+            self,
+            Type::Byte
+                | Type::UnsignedByte
+                | Type::Short
+                | Type::UnsignedShort
+                | Type::Int
+                | Type::UnsignedInt
+        )
+    }
+
+    /// Whether all components share a single 32 bit value, see [`pack_snorm_2_10_10_10_rev`] and friends
+    pub fn is_packed(self) -> bool {
+        matches!(
+            self,
+            Type::Int_2_10_10_10_Rev
+                | Type::UnsignedInt_2_10_10_10_Rev
+                | Type::UnsignedInt_10F_11F_11F_Rev
+        )
+    }
+
+    /// Whether it's accepted by `vertex_attrib_ipointer` and `vertex_attrib_i_format`
+    pub fn valid_for_ipointer(self) -> bool {
+        self.is_integer()
+    }
+
+    /// Whether it's accepted by `vertex_attrib_lpointer` and `vertex_attrib_l_format`
+    pub fn valid_for_lpointer(self) -> bool {
+        self == Type::Double
+    }
+
+    /// The component counts GL accepts as an attribute's `size` for this type
+    pub fn component_counts(self) -> &'static [i32] {
+        match self {
+            Type::Int_2_10_10_10_Rev | Type::UnsignedInt_2_10_10_10_Rev => &[4],
+            Type::UnsignedInt_10F_11F_11F_Rev => &[3],
+            _ => &[1, 2, 3, 4],
         }
     }
 }
 
-#[repr(u32)]
-#[derive(Debug, Clone, Copy, PartialEq, Hash)]
-pub enum DrawMode {
-    Points = rgl::POINTS,
-    LineStrip = rgl::LINE_STRIP,
-    LineLoop = rgl::LINE_LOOP,
-    Lines = rgl::LINES,
-    LineStripAdjacency = rgl::LINE_STRIP_ADJACENCY,
-    LinesAdjacency = rgl::LINES_ADJACENCY,
-    TriangleStrip = rgl::TRIANGLE_STRIP,
-    TriangleFan = rgl::TRIANGLE_FAN,
-    Triangles = rgl::TRIANGLES,
-    TriangleStripAdjacency = rgl::TRIANGLE_STRIP_ADJACENCY,
-    TriangleAdjacency = rgl::TRIANGLES_ADJACENCY,
-    Patches = rgl::PATCHES,
+gl_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Hash)]
+    pub enum DrawMode {
+        Points = rgl::POINTS,
+        LineStrip = rgl::LINE_STRIP,
+        LineLoop = rgl::LINE_LOOP,
+        Lines = rgl::LINES,
+        LineStripAdjacency = rgl::LINE_STRIP_ADJACENCY,
+        LinesAdjacency = rgl::LINES_ADJACENCY,
+        TriangleStrip = rgl::TRIANGLE_STRIP,
+        TriangleFan = rgl::TRIANGLE_FAN,
+        Triangles = rgl::TRIANGLES,
+        TriangleStripAdjacency = rgl::TRIANGLE_STRIP_ADJACENCY,
+        TriangleAdjacency = rgl::TRIANGLES_ADJACENCY,
+        Patches = rgl::PATCHES,
+    }
 }
 
-#[repr(u32)]
-#[derive(Debug, Clone, Copy, PartialEq, Hash)]
-pub enum GlString {
-    Vendor = rgl::VENDOR,
-    Renderer = rgl::RENDERER,
-    Version = rgl::VERSION,
-    ShadingLanguageVersion = rgl::SHADING_LANGUAGE_VERSION,
+gl_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Hash)]
+    pub enum GlString {
+        Vendor = rgl::VENDOR,
+        Renderer = rgl::RENDERER,
+        Version = rgl::VERSION,
+        ShadingLanguageVersion = rgl::SHADING_LANGUAGE_VERSION,
+    }
 }
 
 impl GlString {
@@ -113,10 +260,11 @@ impl GlString {
     }
 }
 
-#[repr(u32)]
-#[derive(Debug, Clone, Copy, PartialEq, Hash)]
-pub enum GlStringI {
-    Extensions = rgl::EXTENSIONS,
+gl_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Hash)]
+    pub enum GlStringI {
+        Extensions = rgl::EXTENSIONS,
+    }
 }
 
 impl GlStringI {
@@ -142,7 +290,7 @@ impl BufferBit {
 }
 
 pub fn clear_color(r: f32, g: f32, b: f32, a: f32) {
-    unsafe { rgl::ClearColor(r, g, b, a) }
+    unsafe { gl!(ClearColor(r, g, b, a)) }
 }
 
 pub fn clear_color_array(array: [f32; 4]) {
@@ -150,27 +298,16 @@ pub fn clear_color_array(array: [f32; 4]) {
 }
 
 pub fn clear(mask: BitFlags<BufferBit>) {
-    unsafe { rgl::Clear(mask.bits()) }
+    unsafe { gl!(Clear(mask.bits())) }
 }
 
 pub fn draw_arrays(mode: DrawMode, first: i32, count: i32) {
-    unsafe { rgl::DrawArrays(mode as u32, first, count) }
-}
-
-pub fn draw_elements(mode: DrawMode, count: i32, ty: Type, indices: Option<*const c_void>) {
-    unsafe {
-        rgl::DrawElements(
-            mode as u32,
-            count,
-            ty as u32,
-            if let Some(i) = indices { i } else { null() },
-        )
-    }
+    unsafe { gl!(DrawArrays(mode as u32, first, count)) }
 }
 
 pub fn get_string(name: GlString) -> String {
     unsafe {
-        let ptr = rgl::GetString(name as u32);
+        let ptr = gl!(GetString(name as u32));
         String::from_utf8(
             std::ffi::CStr::from_ptr(ptr as *const i8)
                 .to_bytes()
@@ -182,7 +319,7 @@ pub fn get_string(name: GlString) -> String {
 
 pub fn get_string_i(name: GlStringI, i: u32) -> String {
     unsafe {
-        let ptr = rgl::GetStringi(name as u32, i);
+        let ptr = gl!(GetStringi(name as u32, i));
         String::from_utf8(
             std::ffi::CStr::from_ptr(ptr as *const i8)
                 .to_bytes()
@@ -192,6 +329,48 @@ pub fn get_string_i(name: GlStringI, i: u32) -> String {
     }
 }
 
+/// The `(major, minor)` version of the current context
+pub fn get_version() -> (i32, i32) {
+    let (mut major, mut minor) = (0, 0);
+    unsafe {
+        gl!(GetIntegerv(rgl::MAJOR_VERSION, &mut major));
+        gl!(GetIntegerv(rgl::MINOR_VERSION, &mut minor));
+    }
+    (major, minor)
+}
+
+/// Whether the current context supports `name`, e.g. `"GL_ARB_buffer_storage"`
+pub fn has_extension(name: &str) -> bool {
+    let mut count: i32 = 0;
+    unsafe { gl!(GetIntegerv(rgl::NUM_EXTENSIONS, &mut count)) };
+    (0..count.max(0) as u32).any(|i| GlStringI::Extensions.get(i) == name)
+}
+
+/// Whether the direct state access functions (`create_buffers`, `named_buffer_data`, ...) can be
+/// used, which needs GL 4.5 or `GL_ARB_direct_state_access`
+pub fn has_direct_state_access() -> bool {
+    get_version() >= (4, 5) || has_extension("GL_ARB_direct_state_access")
+}
+
+/// Panics with a useful message instead of the loader's "function not loaded" one
+fn assert_dsa_loaded(loaded: bool, function: &str) {
+    assert!(
+        loaded,
+        "{} isn't available, direct state access needs GL 4.5 or GL_ARB_direct_state_access",
+        function
+    );
+}
+
+/// Submits all issued commands to the GPU without waiting for them to complete
+pub fn flush() {
+    unsafe { gl!(Flush()) }
+}
+
+/// Blocks until all issued commands have completed
+pub fn finish() {
+    unsafe { gl!(Finish()) }
+}
+
 pub fn viewport(x: i32, y: i32, width: i32, height: i32) {
-    unsafe { rgl::Viewport(x, y, width, height) }
+    unsafe { gl!(Viewport(x, y, width, height)) }
 }
@@ -0,0 +1,117 @@
+//! CPU side encoding of the packed vertex formats, e.g. for compact normals
+
+/// Packs `[x, y, z, w]` for `Type::Int_2_10_10_10_Rev` with `normalized` set, each component
+/// clamped to `-1.0..=1.0`
+pub fn pack_snorm_2_10_10_10_rev(value: [f32; 4]) -> u32 {
+    let snorm = |v: f32, max: f32, bits: u32| {
+        ((v.clamp(-1.0, 1.0) * max).round() as i32 as u32) & ((1 << bits) - 1)
+    };
+    snorm(value[0], 511.0, 10)
+        | snorm(value[1], 511.0, 10) << 10
+        | snorm(value[2], 511.0, 10) << 20
+        | snorm(value[3], 1.0, 2) << 30
+}
+
+/// Inverse of [`pack_snorm_2_10_10_10_rev`], using the GL 4.2 rule where both the smallest
+/// values map to `-1.0`
+pub fn unpack_snorm_2_10_10_10_rev(packed: u32) -> [f32; 4] {
+    // shifting left then arithmetic right sign-extends the component
+    let snorm = |shift: u32, bits: u32, max: f32| {
+        let v = ((packed << (32 - shift - bits)) as i32) >> (32 - bits);
+        (v as f32 / max).max(-1.0)
+    };
+    [
+        snorm(0, 10, 511.0),
+        snorm(10, 10, 511.0),
+        snorm(20, 10, 511.0),
+        snorm(30, 2, 1.0),
+    ]
+}
+
+/// Packs `[x, y, z, w]` for `Type::UnsignedInt_2_10_10_10_Rev` with `normalized` set, each
+/// component clamped to `0.0..=1.0`
+pub fn pack_unorm_2_10_10_10_rev(value: [f32; 4]) -> u32 {
+    let unorm = |v: f32, max: f32| (v.clamp(0.0, 1.0) * max).round() as u32;
+    unorm(value[0], 1023.0)
+        | unorm(value[1], 1023.0) << 10
+        | unorm(value[2], 1023.0) << 20
+        | unorm(value[3], 3.0) << 30
+}
+
+/// Inverse of [`pack_unorm_2_10_10_10_rev`]
+pub fn unpack_unorm_2_10_10_10_rev(packed: u32) -> [f32; 4] {
+    [
+        (packed & 0x3FF) as f32 / 1023.0,
+        (packed >> 10 & 0x3FF) as f32 / 1023.0,
+        (packed >> 20 & 0x3FF) as f32 / 1023.0,
+        (packed >> 30) as f32 / 3.0,
+    ]
+}
+
+/// Packs `[r, g, b]` for `Type::UnsignedInt_10F_11F_11F_Rev`.
+///
+/// The formats have no sign bit so negative values become `0.0`, finite values too large to
+/// represent are clamped to the largest one and infinities and NaN are kept.
+pub fn pack_10f_11f_11f_rev(value: [f32; 3]) -> u32 {
+    to_small_float(value[0], 6)
+        | to_small_float(value[1], 6) << 11
+        | to_small_float(value[2], 5) << 22
+}
+
+/// Inverse of [`pack_10f_11f_11f_rev`]
+pub fn unpack_10f_11f_11f_rev(packed: u32) -> [f32; 3] {
+    [
+        from_small_float(packed & 0x7FF, 6),
+        from_small_float(packed >> 11 & 0x7FF, 6),
+        from_small_float(packed >> 22 & 0x3FF, 5),
+    ]
+}
+
+/// Both small float formats have a 5 bit exponent with a bias of 15, like half floats
+const SMALL_FLOAT_BIAS: i32 = 15;
+const SMALL_FLOAT_MAX_EXPONENT: u32 = 0x1F;
+
+fn to_small_float(value: f32, mantissa_bits: u32) -> u32 {
+    let infinity = SMALL_FLOAT_MAX_EXPONENT << mantissa_bits;
+    let max_finite = infinity - 1;
+    if value.is_nan() {
+        return infinity | 1 << (mantissa_bits - 1);
+    }
+    if value <= 0.0 {
+        return 0;
+    }
+    if value.is_infinite() {
+        return infinity;
+    }
+
+    let bits = value.to_bits();
+    let exponent = ((bits >> 23) & 0xFF) as i32 - 127 + SMALL_FLOAT_BIAS;
+    if exponent <= 0 {
+        // denormal, the implicit leading 1 is gone and the exponent is fixed at 1 - bias
+        let scale = 2f32.powi(SMALL_FLOAT_BIAS - 1 + mantissa_bits as i32);
+        return (value * scale).round() as u32;
+    }
+
+    let shift = 23 - mantissa_bits;
+    let mantissa = bits & 0x7F_FFFF;
+    let mut packed = (exponent as u32) << mantissa_bits | mantissa >> shift;
+    // round to nearest even, a carry out of the mantissa correctly bumps the exponent
+    let remainder = mantissa & ((1 << shift) - 1);
+    let half = 1 << (shift - 1);
+    if remainder > half || (remainder == half && packed & 1 == 1) {
+        packed += 1;
+    }
+    packed.min(max_finite)
+}
+
+fn from_small_float(packed: u32, mantissa_bits: u32) -> f32 {
+    let exponent = packed >> mantissa_bits;
+    let mantissa = packed & ((1 << mantissa_bits) - 1);
+    let fraction = mantissa as f32 / (1 << mantissa_bits) as f32;
+    match exponent {
+        0 => fraction * 2f32.powi(1 - SMALL_FLOAT_BIAS),
+        SMALL_FLOAT_MAX_EXPONENT if mantissa == 0 => f32::INFINITY,
+        SMALL_FLOAT_MAX_EXPONENT => f32::NAN,
+        _ => (1.0 + fraction) * 2f32.powi(exponent as i32 - SMALL_FLOAT_BIAS),
+    }
+}
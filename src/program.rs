@@ -1,35 +1,36 @@
+use super::UnknownEnumValue;
 use shrinkwraprs::Shrinkwrap;
-use std::{ffi::CString, ptr::null_mut};
-
-#[repr(u32)]
-#[derive(Debug, Clone, Copy, PartialEq, Hash)]
-pub enum ShaderKind {
-    Compute = rgl::COMPUTE_SHADER,
-    Vertex = rgl::VERTEX_SHADER,
-    TessControl = rgl::TESS_CONTROL_SHADER,
-    TessEvaluation = rgl::TESS_EVALUATION_SHADER,
-    Geometry = rgl::GEOMETRY_SHADER,
-    Fragment = rgl::FRAGMENT_SHADER,
+use std::{convert::TryFrom, ffi::CString};
+
+gl_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Hash)]
+    pub enum ShaderKind {
+        Compute = rgl::COMPUTE_SHADER,
+        Vertex = rgl::VERTEX_SHADER,
+        TessControl = rgl::TESS_CONTROL_SHADER,
+        TessEvaluation = rgl::TESS_EVALUATION_SHADER,
+        Geometry = rgl::GEOMETRY_SHADER,
+        Fragment = rgl::FRAGMENT_SHADER,
+    }
 }
 
-#[repr(u32)]
-#[derive(Debug, Clone, Copy, PartialEq, Hash)]
-pub enum ShaderProp {
-    Type = rgl::SHADER_TYPE,
-    DeleteStatus = rgl::DELETE_STATUS,
-    CompileStatus = rgl::COMPILE_STATUS,
-    InfoLogLength = rgl::INFO_LOG_LENGTH,
-    SourceLength = rgl::SHADER_SOURCE_LENGTH,
+gl_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Hash)]
+    pub enum ShaderProp {
+        Type = rgl::SHADER_TYPE,
+        DeleteStatus = rgl::DELETE_STATUS,
+        CompileStatus = rgl::COMPILE_STATUS,
+        InfoLogLength = rgl::INFO_LOG_LENGTH,
+        SourceLength = rgl::SHADER_SOURCE_LENGTH,
+    }
 }
 
 // TODO: rest of the gets
 impl ShaderProp {
-    pub fn get_type(shader: Shader) -> ShaderKind {
+    pub fn get_type(shader: Shader) -> Result<ShaderKind, UnknownEnumValue> {
         let mut kind: i32 = 0;
-        unsafe {
-            get_shader_iv(shader, Self::Type, &mut kind);
-            std::mem::transmute(kind) // don't do this
-        }
+        unsafe { get_shader_iv(shader, Self::Type, &mut kind) };
+        ShaderKind::try_from(kind as u32)
     }
 
     pub fn get_info_log_length(shader: Shader) -> i32 {
@@ -45,29 +46,30 @@ impl ShaderProp {
     }
 }
 
-#[repr(u32)]
-#[derive(Debug, Clone, Copy, PartialEq, Hash)]
-pub enum ProgramProp {
-    DeleteStatus = rgl::DELETE_STATUS,
-    LinkStatus = rgl::LINK_STATUS,
-    ValidateStatus = rgl::VALIDATE_STATUS,
-    InfoLogLength = rgl::INFO_LOG_LENGTH,
-    AttachedShaders = rgl::ATTACHED_SHADERS,
-    ActiveAtomicCounterBuffers = rgl::ACTIVE_ATOMIC_COUNTER_BUFFERS,
-    ActiveAttributes = rgl::ACTIVE_ATTRIBUTES,
-    ActiveAttributeMaxLength = rgl::ACTIVE_ATTRIBUTE_MAX_LENGTH,
-    ActiveUniforms = rgl::ACTIVE_UNIFORMS,
-    ActiveUniformBlocks = rgl::ACTIVE_UNIFORM_BLOCKS,
-    ActiveUniformBlockMaxNameLength = rgl::ACTIVE_UNIFORM_BLOCK_MAX_NAME_LENGTH,
-    ActiveUniformMaxLength = rgl::ACTIVE_UNIFORM_MAX_LENGTH,
-    ComputeWorkGroupSize = rgl::COMPUTE_WORK_GROUP_SIZE,
-    ProgramBinaryLength = rgl::PROGRAM_BINARY_LENGTH,
-    TransformFeedbackBufferMode = rgl::TRANSFORM_FEEDBACK_BUFFER_MODE,
-    TransformFeedbackVaryings = rgl::TRANSFORM_FEEDBACK_VARYINGS,
-    TransformFeedbackVaryingMaxLength = rgl::TRANSFORM_FEEDBACK_VARYING_MAX_LENGTH,
-    GeometryVerticesOut = rgl::GEOMETRY_VERTICES_OUT,
-    GeometryInputType = rgl::GEOMETRY_INPUT_TYPE,
-    GeometryOutputType = rgl::GEOMETRY_OUTPUT_TYPE,
+gl_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Hash)]
+    pub enum ProgramProp {
+        DeleteStatus = rgl::DELETE_STATUS,
+        LinkStatus = rgl::LINK_STATUS,
+        ValidateStatus = rgl::VALIDATE_STATUS,
+        InfoLogLength = rgl::INFO_LOG_LENGTH,
+        AttachedShaders = rgl::ATTACHED_SHADERS,
+        ActiveAtomicCounterBuffers = rgl::ACTIVE_ATOMIC_COUNTER_BUFFERS,
+        ActiveAttributes = rgl::ACTIVE_ATTRIBUTES,
+        ActiveAttributeMaxLength = rgl::ACTIVE_ATTRIBUTE_MAX_LENGTH,
+        ActiveUniforms = rgl::ACTIVE_UNIFORMS,
+        ActiveUniformBlocks = rgl::ACTIVE_UNIFORM_BLOCKS,
+        ActiveUniformBlockMaxNameLength = rgl::ACTIVE_UNIFORM_BLOCK_MAX_NAME_LENGTH,
+        ActiveUniformMaxLength = rgl::ACTIVE_UNIFORM_MAX_LENGTH,
+        ComputeWorkGroupSize = rgl::COMPUTE_WORK_GROUP_SIZE,
+        ProgramBinaryLength = rgl::PROGRAM_BINARY_LENGTH,
+        TransformFeedbackBufferMode = rgl::TRANSFORM_FEEDBACK_BUFFER_MODE,
+        TransformFeedbackVaryings = rgl::TRANSFORM_FEEDBACK_VARYINGS,
+        TransformFeedbackVaryingMaxLength = rgl::TRANSFORM_FEEDBACK_VARYING_MAX_LENGTH,
+        GeometryVerticesOut = rgl::GEOMETRY_VERTICES_OUT,
+        GeometryInputType = rgl::GEOMETRY_INPUT_TYPE,
+        GeometryOutputType = rgl::GEOMETRY_OUTPUT_TYPE,
+    }
 }
 
 // TODO: rest of the gets
@@ -97,20 +99,165 @@ pub struct Program(u32);
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Shrinkwrap)]
 pub struct UniformLocation(i32);
 
+/// Returned by [`Shader::compile`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShaderError {
+    pub kind: ShaderKind,
+    pub source: String,
+    pub log: String,
+}
+
+impl std::fmt::Display for ShaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "failed to compile {:?} shader: {}", self.kind, self.log)
+    }
+}
+
+impl std::error::Error for ShaderError {}
+
+/// Returned by [`Program::link`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkError {
+    pub log: String,
+}
+
+impl std::fmt::Display for LinkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "failed to link program: {}", self.log)
+    }
+}
+
+impl std::error::Error for LinkError {}
+
+impl Shader {
+    /// Creates and compiles a shader, deleting it again if compilation fails
+    pub fn compile(kind: ShaderKind, src: &str) -> Result<Shader, ShaderError> {
+        let shader = create_shader(kind);
+        shader_source(shader, src);
+        compile_shader(shader);
+
+        if ShaderProp::get_compile_status(shader) {
+            return Ok(shader);
+        }
+
+        let log = get_shader_info_log(shader);
+        delete_shader(shader);
+        Err(ShaderError {
+            kind,
+            source: src.to_string(),
+            log,
+        })
+    }
+}
+
+impl Program {
+    /// Creates a program from `shaders` and links it, deleting it again if linking fails.
+    ///
+    /// The shaders are detached afterwards so they can be deleted independently of the program.
+    pub fn link(shaders: &[Shader]) -> Result<Program, LinkError> {
+        let program = create_program();
+        for shader in shaders {
+            attach_shader(program, *shader);
+        }
+        link_program(program);
+        for shader in shaders {
+            detach_shader(program, *shader);
+        }
+
+        if ProgramProp::get_link_status(program) {
+            return Ok(program);
+        }
+
+        let log = get_program_info_log(program);
+        delete_program(program);
+        Err(LinkError { log })
+    }
+}
+
+/// A [`Shader`] that is deleted when dropped
+#[derive(Debug, PartialEq, Eq, Hash, Shrinkwrap)]
+pub struct OwnedShader(Shader);
+
+impl OwnedShader {
+    pub fn new(kind: ShaderKind) -> Self {
+        Self(create_shader(kind))
+    }
+
+    /// Takes ownership of `shader`, it will be deleted when the returned value is dropped
+    pub fn from_raw(shader: Shader) -> Self {
+        Self(shader)
+    }
+
+    pub fn handle(&self) -> Shader {
+        self.0
+    }
+
+    /// Releases ownership without deleting, the caller is responsible for [`delete_shader`]
+    pub fn into_raw(self) -> Shader {
+        let shader = self.0;
+        std::mem::forget(self);
+        shader
+    }
+}
+
+impl Drop for OwnedShader {
+    fn drop(&mut self) {
+        delete_shader(self.0)
+    }
+}
+
+/// A [`Program`] that is deleted when dropped
+#[derive(Debug, PartialEq, Eq, Hash, Shrinkwrap)]
+pub struct OwnedProgram(Program);
+
+impl OwnedProgram {
+    pub fn new() -> Self {
+        Self(create_program())
+    }
+
+    /// Takes ownership of `program`, it will be deleted when the returned value is dropped
+    pub fn from_raw(program: Program) -> Self {
+        Self(program)
+    }
+
+    pub fn handle(&self) -> Program {
+        self.0
+    }
+
+    /// Releases ownership without deleting, the caller is responsible for [`delete_program`]
+    pub fn into_raw(self) -> Program {
+        let program = self.0;
+        std::mem::forget(self);
+        program
+    }
+}
+
+impl Default for OwnedProgram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for OwnedProgram {
+    fn drop(&mut self) {
+        delete_program(self.0)
+    }
+}
+
 pub fn create_shader(kind: ShaderKind) -> Shader {
-    let int = unsafe { rgl::CreateShader(kind as u32) };
+    let int = unsafe { gl!(CreateShader(kind as u32)) };
     Shader(int)
 }
 
 pub fn shader_source(shader: Shader, src: &str) {
     let str_ptr = [src.as_ptr()].as_ptr() as *const *const i8;
     let len_ptr = [src.len() as i32].as_ptr();
-    unsafe { rgl::ShaderSource(shader.0, 1, str_ptr, len_ptr) };
+    unsafe { gl!(ShaderSource(shader.0, 1, str_ptr, len_ptr)) };
 }
 
 /// Prefer [`ShaderProp`]'s get functions
 pub unsafe fn get_shader_iv(shader: Shader, pname: ShaderProp, params: *mut i32) {
-    rgl::GetShaderiv(shader.0, pname as u32, params);
+    gl!(GetShaderiv(shader.0, pname as u32, params));
 }
 
 /// Prefer [`get_shader_info_log`]
@@ -120,7 +267,13 @@ pub unsafe fn get_shader_info_log_raw(
     length: *mut i32,
     info_log: *mut i8,
 ) {
-    rgl::GetShaderInfoLog(shader.0, buf_size, length, info_log);
+    gl!(GetShaderInfoLog(shader.0, buf_size, length, info_log));
+}
+
+/// Drivers aren't required to write valid UTF-8, so invalid sequences are replaced
+fn info_log_to_string(log: &[u8]) -> String {
+    let log = log.strip_suffix(&[0]).unwrap_or(log);
+    String::from_utf8_lossy(log).into_owned()
 }
 
 pub fn get_shader_info_log(shader: Shader) -> String {
@@ -131,45 +284,51 @@ pub fn get_shader_info_log(shader: Shader) -> String {
         return "".to_string();
     }
 
-    let log_len = log_len - 1;
-    let mut vec = vec![0; log_len as usize];
-    unsafe { get_shader_info_log_raw(shader, log_len, null_mut(), vec.as_mut_ptr() as *mut i8) };
+    // the length includes the NUL terminator, which GL writes within `buf_size`
+    let mut vec = vec![0u8; log_len as usize];
+    let mut written: i32 = 0;
+    unsafe { get_shader_info_log_raw(shader, log_len, &mut written, vec.as_mut_ptr() as *mut i8) };
+    vec.truncate(written.clamp(0, log_len) as usize);
 
-    String::from_utf8(vec).unwrap()
+    info_log_to_string(&vec)
 }
 
 pub fn delete_shader(shader: Shader) {
-    unsafe { rgl::DeleteShader(shader.0) }
+    unsafe { gl!(DeleteShader(shader.0)) }
 }
 
 pub fn compile_shader(shader: Shader) {
-    unsafe { rgl::CompileShader(shader.0) };
+    unsafe { gl!(CompileShader(shader.0)) };
 }
 
 pub fn create_program() -> Program {
-    let int = unsafe { rgl::CreateProgram() };
+    let int = unsafe { gl!(CreateProgram()) };
     Program(int)
 }
 
 pub fn attach_shader(program: Program, shader: Shader) {
-    unsafe { rgl::AttachShader(program.0, shader.0) }
+    unsafe { gl!(AttachShader(program.0, shader.0)) }
+}
+
+pub fn detach_shader(program: Program, shader: Shader) {
+    unsafe { gl!(DetachShader(program.0, shader.0)) }
 }
 
 pub fn link_program(program: Program) {
-    unsafe { rgl::LinkProgram(program.0) }
+    unsafe { gl!(LinkProgram(program.0)) }
 }
 
 pub fn use_program(program: Program) {
-    unsafe { rgl::UseProgram(program.0) }
+    unsafe { gl!(UseProgram(program.0)) }
 }
 
 pub fn delete_program(program: Program) {
-    unsafe { rgl::DeleteProgram(program.0) }
+    unsafe { gl!(DeleteProgram(program.0)) }
 }
 
 /// Prefer [`ProgramProp`]'s get functions
 pub unsafe fn get_program_iv(program: Program, pname: ProgramProp, params: *mut i32) {
-    rgl::GetProgramiv(program.0, pname as u32, params);
+    gl!(GetProgramiv(program.0, pname as u32, params));
 }
 
 /// Prefer [`get_shader_info_log`]
@@ -179,7 +338,7 @@ pub unsafe fn get_program_info_log_raw(
     length: *mut i32,
     info_log: *mut i8,
 ) {
-    rgl::GetProgramInfoLog(*program, buf_size, length, info_log);
+    gl!(GetProgramInfoLog(*program, buf_size, length, info_log));
 }
 
 pub fn get_program_info_log(program: Program) -> String {
@@ -190,21 +349,25 @@ pub fn get_program_info_log(program: Program) -> String {
         return "".to_string();
     }
 
-    let log_len = log_len - 1;
-    let mut vec = vec![0; log_len as usize];
-    unsafe { get_program_info_log_raw(program, log_len, null_mut(), vec.as_mut_ptr() as *mut i8) };
+    // the length includes the NUL terminator, which GL writes within `buf_size`
+    let mut vec = vec![0u8; log_len as usize];
+    let mut written: i32 = 0;
+    unsafe {
+        get_program_info_log_raw(program, log_len, &mut written, vec.as_mut_ptr() as *mut i8)
+    };
+    vec.truncate(written.clamp(0, log_len) as usize);
 
-    String::from_utf8(vec).unwrap()
+    info_log_to_string(&vec)
 }
 
 pub fn get_uniform_location(program: Program, name: &str) -> UniformLocation {
     let name = CString::new(name).unwrap();
-    let int = unsafe { gl::GetUniformLocation(*program, name.as_ptr()) };
+    let int = unsafe { gl!(GetUniformLocation(*program, name.as_ptr())) };
     UniformLocation(int)
 }
 
 pub fn uniform_4f(location: UniformLocation, v0: f32, v1: f32, v2: f32, v3: f32) {
-    unsafe { gl::Uniform4f(*location, v0, v1, v2, v3) }
+    unsafe { gl!(Uniform4f(*location, v0, v1, v2, v3)) }
 }
 
 pub fn uniform_4fs(location: UniformLocation, v: [f32; 4]) {
@@ -212,5 +375,5 @@ pub fn uniform_4fs(location: UniformLocation, v: [f32; 4]) {
 }
 
 pub fn uniform_1f(location: UniformLocation, v0: f32) {
-    unsafe { gl::Uniform1f(*location, v0) }
+    unsafe { gl!(Uniform1f(*location, v0)) }
 }
@@ -0,0 +1,379 @@
+use super::{
+    has_anisotropic_filtering, CompareFunc, MagFilter, MinFilter, SamplerParameters, TextureProp,
+    WrapMode,
+};
+use shrinkwraprs::Shrinkwrap;
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+};
+
+/// Sampling state that can be bound to a texture unit, overriding that of the texture bound
+/// there
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Shrinkwrap)]
+pub struct Sampler(u32);
+
+impl Sampler {
+    pub const NONE: Self = Self(0);
+}
+
+/// A [`Sampler`] that is deleted when dropped
+#[derive(Debug, PartialEq, Eq, Hash, Shrinkwrap)]
+pub struct OwnedSampler(Sampler);
+
+impl OwnedSampler {
+    pub fn new() -> Self {
+        Self(gen_sampler())
+    }
+
+    /// Takes ownership of `sampler`, it will be deleted when the returned value is dropped
+    pub fn from_raw(sampler: Sampler) -> Self {
+        Self(sampler)
+    }
+
+    pub fn handle(&self) -> Sampler {
+        self.0
+    }
+
+    /// Releases ownership without deleting, the caller is responsible for [`delete_sampler`]
+    pub fn into_raw(self) -> Sampler {
+        let sampler = self.0;
+        std::mem::forget(self);
+        sampler
+    }
+}
+
+impl Default for OwnedSampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for OwnedSampler {
+    fn drop(&mut self) {
+        delete_sampler(self.0)
+    }
+}
+
+pub fn gen_samplers<const N: usize>() -> [Sampler; N] {
+    assert!(N < u32::MAX as usize);
+    let mut samplers = [Sampler(0); N];
+    unsafe { gl!(GenSamplers(N as i32, samplers.as_mut_ptr() as *mut u32)) };
+    samplers
+}
+
+pub fn gen_sampler() -> Sampler {
+    gen_samplers::<1>()[0]
+}
+
+/// Deletes every sampler in `samplers`, skipping [`Sampler::NONE`]
+pub fn delete_samplers(samplers: &[Sampler]) {
+    let names: Vec<u32> = samplers
+        .iter()
+        .filter(|s| **s != Sampler::NONE)
+        .map(|s| s.0)
+        .collect();
+    if names.is_empty() {
+        return;
+    }
+    assert!(names.len() < i32::MAX as usize);
+    unsafe { gl!(DeleteSamplers(names.len() as i32, names.as_ptr())) }
+}
+
+pub fn delete_sampler(sampler: Sampler) {
+    delete_samplers(&[sampler])
+}
+
+/// Binds `sampler` to texture unit `unit`, counting from 0 like `active_texture`.
+/// [`Sampler::NONE`] goes back to using the texture's own parameters.
+pub fn bind_sampler(unit: u32, sampler: Sampler) {
+    unsafe { gl!(BindSampler(unit, sampler.0)) }
+}
+
+pub fn sampler_parameter_i(sampler: Sampler, prop: TextureProp, param: i32) {
+    unsafe { gl!(SamplerParameteri(sampler.0, prop as u32, param)) }
+}
+
+pub fn sampler_parameter_f(sampler: Sampler, prop: TextureProp, param: f32) {
+    unsafe { gl!(SamplerParameterf(sampler.0, prop as u32, param)) }
+}
+
+/// `params` must hold [`TextureProp::components`] values
+pub fn sampler_parameter_iv(sampler: Sampler, prop: TextureProp, params: &[i32]) {
+    prop.assert_components(params.len());
+    unsafe { gl!(SamplerParameteriv(sampler.0, prop as u32, params.as_ptr())) }
+}
+
+/// `params` must hold [`TextureProp::components`] values
+pub fn sampler_parameter_fv(sampler: Sampler, prop: TextureProp, params: &[f32]) {
+    prop.assert_components(params.len());
+    unsafe { gl!(SamplerParameterfv(sampler.0, prop as u32, params.as_ptr())) }
+}
+
+/// Like [`sampler_parameter_iv`] but the border color isn't normalized for integer textures
+pub fn sampler_parameter_i_iv(sampler: Sampler, prop: TextureProp, params: &[i32]) {
+    prop.assert_components(params.len());
+    unsafe { gl!(SamplerParameterIiv(sampler.0, prop as u32, params.as_ptr())) }
+}
+
+/// Like [`sampler_parameter_i_iv`] for unsigned integer textures
+pub fn sampler_parameter_i_uiv(sampler: Sampler, prop: TextureProp, params: &[u32]) {
+    prop.assert_components(params.len());
+    unsafe {
+        gl!(SamplerParameterIuiv(
+            sampler.0,
+            prop as u32,
+            params.as_ptr()
+        ))
+    }
+}
+
+/// `params` must hold [`TextureProp::components`] values
+pub fn get_sampler_parameter_iv(sampler: Sampler, prop: TextureProp, params: &mut [i32]) {
+    prop.assert_components(params.len());
+    unsafe {
+        gl!(GetSamplerParameteriv(
+            sampler.0,
+            prop as u32,
+            params.as_mut_ptr()
+        ))
+    }
+}
+
+/// `params` must hold [`TextureProp::components`] values
+pub fn get_sampler_parameter_fv(sampler: Sampler, prop: TextureProp, params: &mut [f32]) {
+    prop.assert_components(params.len());
+    unsafe {
+        gl!(GetSamplerParameterfv(
+            sampler.0,
+            prop as u32,
+            params.as_mut_ptr()
+        ))
+    }
+}
+
+/// `params` must hold [`TextureProp::components`] values
+pub fn get_sampler_parameter_i_iv(sampler: Sampler, prop: TextureProp, params: &mut [i32]) {
+    prop.assert_components(params.len());
+    unsafe {
+        gl!(GetSamplerParameterIiv(
+            sampler.0,
+            prop as u32,
+            params.as_mut_ptr()
+        ))
+    }
+}
+
+/// `params` must hold [`TextureProp::components`] values
+pub fn get_sampler_parameter_i_uiv(sampler: Sampler, prop: TextureProp, params: &mut [u32]) {
+    prop.assert_components(params.len());
+    unsafe {
+        gl!(GetSamplerParameterIuiv(
+            sampler.0,
+            prop as u32,
+            params.as_mut_ptr()
+        ))
+    }
+}
+
+impl SamplerParameters for Sampler {
+    fn parameter_i(self, prop: TextureProp, param: i32) {
+        sampler_parameter_i(self, prop, param)
+    }
+
+    fn parameter_f(self, prop: TextureProp, param: f32) {
+        sampler_parameter_f(self, prop, param)
+    }
+
+    fn parameter_iv(self, prop: TextureProp, params: &[i32]) {
+        sampler_parameter_iv(self, prop, params)
+    }
+
+    fn parameter_fv(self, prop: TextureProp, params: &[f32]) {
+        sampler_parameter_fv(self, prop, params)
+    }
+
+    fn parameter_i_iv(self, prop: TextureProp, params: &[i32]) {
+        sampler_parameter_i_iv(self, prop, params)
+    }
+
+    fn parameter_i_uiv(self, prop: TextureProp, params: &[u32]) {
+        sampler_parameter_i_uiv(self, prop, params)
+    }
+
+    fn get_parameter_iv(self, prop: TextureProp, params: &mut [i32]) {
+        get_sampler_parameter_iv(self, prop, params)
+    }
+
+    fn get_parameter_fv(self, prop: TextureProp, params: &mut [f32]) {
+        get_sampler_parameter_fv(self, prop, params)
+    }
+
+    fn get_parameter_i_iv(self, prop: TextureProp, params: &mut [i32]) {
+        get_sampler_parameter_i_iv(self, prop, params)
+    }
+
+    fn get_parameter_i_uiv(self, prop: TextureProp, params: &mut [u32]) {
+        get_sampler_parameter_i_uiv(self, prop, params)
+    }
+}
+
+/// Every parameter of a [`Sampler`] as a plain value, defaulting to GL's initial state.
+///
+/// Floats are compared and hashed by their bits, so descriptions can key a [`SamplerCache`].
+/// The lod range must not be empty and anisotropy must be at least 1.0, neither may be NaN.
+#[derive(Debug, Clone, Copy)]
+pub struct SamplerDesc {
+    /// S, T and R
+    pub wrap: [WrapMode; 3],
+    pub min_filter: MinFilter,
+    pub mag_filter: MagFilter,
+    pub compare: Option<CompareFunc>,
+    pub border_color: [f32; 4],
+    /// `(min, max)`
+    pub lod_range: (f32, f32),
+    pub lod_bias: f32,
+    /// 1.0 disables anisotropic filtering
+    pub anisotropy: f32,
+}
+
+impl SamplerDesc {
+    /// Sets every parameter of `target`, e.g. a [`Sampler`] or a bound `TextureTarget`.
+    ///
+    /// Anisotropy is only skipped when it's 1.0 and anisotropic filtering isn't supported, which
+    /// costs a few `glGet*` calls to find out. Panics before setting anything if the description
+    /// is invalid.
+    pub fn apply<S: SamplerParameters>(&self, target: S) {
+        self.apply_with(target, has_anisotropic_filtering())
+    }
+
+    fn apply_with<S: SamplerParameters>(&self, target: S, anisotropic: bool) {
+        self.assert_valid();
+        target.set_wrap_s(self.wrap[0]);
+        target.set_wrap_t(self.wrap[1]);
+        target.set_wrap_r(self.wrap[2]);
+        target.set_min_filter(self.min_filter);
+        target.set_mag_filter(self.mag_filter);
+        target.set_compare(self.compare);
+        target.set_border_color(self.border_color);
+        target.set_lod_range(self.lod_range.0, self.lod_range.1);
+        target.set_lod_bias(self.lod_bias);
+        // setting it is an error without anisotropic filtering support
+        if anisotropic || self.anisotropy != 1.0 {
+            target.set_anisotropy(self.anisotropy);
+        }
+    }
+
+    fn assert_valid(&self) {
+        let (min, max) = self.lod_range;
+        assert!(min <= max, "lod range {}..={} is empty", min, max);
+        assert!(
+            self.anisotropy >= 1.0,
+            "anisotropy must be at least 1.0, got {}",
+            self.anisotropy
+        );
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn key(
+        &self,
+    ) -> (
+        [WrapMode; 3],
+        MinFilter,
+        MagFilter,
+        Option<CompareFunc>,
+        [u32; 8],
+    ) {
+        let [r, g, b, a] = self.border_color.map(f32::to_bits);
+        let floats = [
+            r,
+            g,
+            b,
+            a,
+            self.lod_range.0.to_bits(),
+            self.lod_range.1.to_bits(),
+            self.lod_bias.to_bits(),
+            self.anisotropy.to_bits(),
+        ];
+        (
+            self.wrap,
+            self.min_filter,
+            self.mag_filter,
+            self.compare,
+            floats,
+        )
+    }
+}
+
+impl Default for SamplerDesc {
+    fn default() -> Self {
+        Self {
+            wrap: [WrapMode::Repeat; 3],
+            min_filter: MinFilter::NearestMipmapLinear,
+            mag_filter: MagFilter::Linear,
+            compare: None,
+            border_color: [0.0; 4],
+            lod_range: (-1000.0, 1000.0),
+            lod_bias: 0.0,
+            anisotropy: 1.0,
+        }
+    }
+}
+
+impl PartialEq for SamplerDesc {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for SamplerDesc {}
+
+impl Hash for SamplerDesc {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state)
+    }
+}
+
+/// Hands out one shared [`Sampler`] per distinct [`SamplerDesc`], deleting them all when dropped
+#[derive(Debug, Default)]
+pub struct SamplerCache {
+    samplers: HashMap<SamplerDesc, OwnedSampler>,
+    /// [`has_anisotropic_filtering`], looked up by the first [`SamplerCache::get`]
+    anisotropic: Option<bool>,
+}
+
+impl SamplerCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The sampler for `desc`, created the first time it's asked for.
+    /// Panics if `desc` is invalid, see [`SamplerDesc::apply`].
+    pub fn get(&mut self, desc: &SamplerDesc) -> Sampler {
+        let anisotropic = *self
+            .anisotropic
+            .get_or_insert_with(has_anisotropic_filtering);
+        self.samplers
+            .entry(*desc)
+            .or_insert_with(|| {
+                let sampler = OwnedSampler::new();
+                desc.apply_with(*sampler, anisotropic);
+                sampler
+            })
+            .handle()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samplers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samplers.is_empty()
+    }
+
+    /// Deletes every sampler, handles returned by [`SamplerCache::get`] become invalid
+    pub fn clear(&mut self) {
+        self.samplers.clear()
+    }
+}
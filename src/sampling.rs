@@ -0,0 +1,232 @@
+use super::{get_version, has_extension, TextureProp, UnknownEnumValue};
+use std::convert::TryFrom;
+
+gl_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum WrapMode {
+        Repeat = rgl::REPEAT,
+        MirroredRepeat = rgl::MIRRORED_REPEAT,
+        ClampToEdge = rgl::CLAMP_TO_EDGE,
+        ClampToBorder = rgl::CLAMP_TO_BORDER,
+        MirrorClampToEdge = rgl::MIRROR_CLAMP_TO_EDGE,
+    }
+}
+
+gl_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum MinFilter {
+        Nearest = rgl::NEAREST,
+        Linear = rgl::LINEAR,
+        NearestMipmapNearest = rgl::NEAREST_MIPMAP_NEAREST,
+        LinearMipmapNearest = rgl::LINEAR_MIPMAP_NEAREST,
+        NearestMipmapLinear = rgl::NEAREST_MIPMAP_LINEAR,
+        LinearMipmapLinear = rgl::LINEAR_MIPMAP_LINEAR,
+    }
+}
+
+gl_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum MagFilter {
+        Nearest = rgl::NEAREST,
+        Linear = rgl::LINEAR,
+    }
+}
+
+gl_enum! {
+    /// How a depth texture's value is compared against the reference from a shadow sampler
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum CompareFunc {
+        Never = rgl::NEVER,
+        Less = rgl::LESS,
+        Equal = rgl::EQUAL,
+        LessEqual = rgl::LEQUAL,
+        Greater = rgl::GREATER,
+        NotEqual = rgl::NOTEQUAL,
+        GreaterEqual = rgl::GEQUAL,
+        Always = rgl::ALWAYS,
+    }
+}
+
+gl_enum! {
+    /// Where a component read by the shader comes from
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Swizzle {
+        Red = rgl::RED,
+        Green = rgl::GREEN,
+        Blue = rgl::BLUE,
+        Alpha = rgl::ALPHA,
+        Zero = rgl::ZERO,
+        One = rgl::ONE,
+    }
+}
+
+/// Core in GL 4.6, same value as `GL_TEXTURE_MAX_ANISOTROPY_EXT`
+pub(crate) const TEXTURE_MAX_ANISOTROPY: u32 = 0x84FE;
+pub(crate) const MAX_TEXTURE_MAX_ANISOTROPY: u32 = 0x84FF;
+
+/// Whether [`SamplerParameters::set_anisotropy`] can be used, which needs GL 4.6 or
+/// `GL_ARB_texture_filter_anisotropic`/`GL_EXT_texture_filter_anisotropic`
+pub fn has_anisotropic_filtering() -> bool {
+    get_version() >= (4, 6)
+        || has_extension("GL_ARB_texture_filter_anisotropic")
+        || has_extension("GL_EXT_texture_filter_anisotropic")
+}
+
+/// The largest value [`SamplerParameters::set_anisotropy`] accepts, 1.0 if anisotropic
+/// filtering isn't supported at all
+pub fn max_anisotropy() -> f32 {
+    if !has_anisotropic_filtering() {
+        return 1.0;
+    }
+    let mut max: f32 = 1.0;
+    unsafe { gl!(GetFloatv(MAX_TEXTURE_MAX_ANISOTROPY, &mut max)) };
+    max.max(1.0)
+}
+
+/// Something with sampling state, i.e. the texture bound to a [`TextureTarget`](super::TextureTarget)
+///
+/// Only the raw parameter functions need implementing, the typed setters and getters are built
+/// on top of them.
+pub trait SamplerParameters: Copy {
+    fn parameter_i(self, prop: TextureProp, param: i32);
+    fn parameter_f(self, prop: TextureProp, param: f32);
+    fn parameter_iv(self, prop: TextureProp, params: &[i32]);
+    fn parameter_fv(self, prop: TextureProp, params: &[f32]);
+    /// Like [`SamplerParameters::parameter_iv`] but integer texture border colors aren't
+    /// normalized
+    fn parameter_i_iv(self, prop: TextureProp, params: &[i32]);
+    fn parameter_i_uiv(self, prop: TextureProp, params: &[u32]);
+
+    fn get_parameter_iv(self, prop: TextureProp, params: &mut [i32]);
+    fn get_parameter_fv(self, prop: TextureProp, params: &mut [f32]);
+    fn get_parameter_i_iv(self, prop: TextureProp, params: &mut [i32]);
+    fn get_parameter_i_uiv(self, prop: TextureProp, params: &mut [u32]);
+
+    fn get_parameter_i(self, prop: TextureProp) -> i32 {
+        let mut param = [0];
+        self.get_parameter_iv(prop, &mut param);
+        param[0]
+    }
+
+    fn get_parameter_f(self, prop: TextureProp) -> f32 {
+        let mut param = [0.0];
+        self.get_parameter_fv(prop, &mut param);
+        param[0]
+    }
+
+    /// Sets the wrap mode of every axis
+    fn set_wrap(self, mode: WrapMode) {
+        self.set_wrap_s(mode);
+        self.set_wrap_t(mode);
+        self.set_wrap_r(mode);
+    }
+
+    fn set_wrap_s(self, mode: WrapMode) {
+        self.parameter_i(TextureProp::TextureWrapS, mode as i32)
+    }
+
+    fn set_wrap_t(self, mode: WrapMode) {
+        self.parameter_i(TextureProp::TextureWrapT, mode as i32)
+    }
+
+    fn set_wrap_r(self, mode: WrapMode) {
+        self.parameter_i(TextureProp::TextureWrapR, mode as i32)
+    }
+
+    fn set_min_filter(self, filter: MinFilter) {
+        self.parameter_i(TextureProp::TextureMinFilter, filter as i32)
+    }
+
+    fn set_mag_filter(self, filter: MagFilter) {
+        self.parameter_i(TextureProp::TextureMagFilter, filter as i32)
+    }
+
+    /// Turns a depth texture into a shadow map compared with `func`, or back into a plain
+    /// texture with `None`
+    fn set_compare(self, func: Option<CompareFunc>) {
+        match func {
+            Some(func) => {
+                self.parameter_i(
+                    TextureProp::TextureCompareMode,
+                    rgl::COMPARE_REF_TO_TEXTURE as i32,
+                );
+                self.parameter_i(TextureProp::TextureCompareFunc, func as i32);
+            }
+            None => self.parameter_i(TextureProp::TextureCompareMode, rgl::NONE as i32),
+        }
+    }
+
+    /// Used with [`WrapMode::ClampToBorder`]
+    fn set_border_color(self, color: [f32; 4]) {
+        self.parameter_fv(TextureProp::TextureBorderColor, &color)
+    }
+
+    /// Clamps the level of detail, i.e. which mip levels may be sampled
+    fn set_lod_range(self, min: f32, max: f32) {
+        assert!(min <= max, "lod range {}..={} is empty", min, max);
+        self.parameter_f(TextureProp::TextureMinLod, min);
+        self.parameter_f(TextureProp::TextureMaxLod, max);
+    }
+
+    fn set_lod_bias(self, bias: f32) {
+        self.parameter_f(TextureProp::TextureLodBias, bias)
+    }
+
+    /// 1.0 disables anisotropic filtering, see [`max_anisotropy`] for the upper limit
+    fn set_anisotropy(self, anisotropy: f32) {
+        assert!(
+            anisotropy >= 1.0,
+            "anisotropy must be at least 1.0, got {}",
+            anisotropy
+        );
+        self.parameter_f(TextureProp::TextureMaxAnisotropy, anisotropy)
+    }
+
+    /// The wrap modes of the S, T and R axes
+    fn get_wrap(self) -> Result<[WrapMode; 3], UnknownEnumValue> {
+        Ok([
+            WrapMode::try_from(self.get_parameter_i(TextureProp::TextureWrapS) as u32)?,
+            WrapMode::try_from(self.get_parameter_i(TextureProp::TextureWrapT) as u32)?,
+            WrapMode::try_from(self.get_parameter_i(TextureProp::TextureWrapR) as u32)?,
+        ])
+    }
+
+    fn get_min_filter(self) -> Result<MinFilter, UnknownEnumValue> {
+        MinFilter::try_from(self.get_parameter_i(TextureProp::TextureMinFilter) as u32)
+    }
+
+    fn get_mag_filter(self) -> Result<MagFilter, UnknownEnumValue> {
+        MagFilter::try_from(self.get_parameter_i(TextureProp::TextureMagFilter) as u32)
+    }
+
+    /// `None` if depth comparison is off
+    fn get_compare(self) -> Result<Option<CompareFunc>, UnknownEnumValue> {
+        if self.get_parameter_i(TextureProp::TextureCompareMode) as u32 == rgl::NONE {
+            return Ok(None);
+        }
+        CompareFunc::try_from(self.get_parameter_i(TextureProp::TextureCompareFunc) as u32)
+            .map(Some)
+    }
+
+    fn get_border_color(self) -> [f32; 4] {
+        let mut color = [0.0; 4];
+        self.get_parameter_fv(TextureProp::TextureBorderColor, &mut color);
+        color
+    }
+
+    /// `(min, max)`
+    fn get_lod_range(self) -> (f32, f32) {
+        (
+            self.get_parameter_f(TextureProp::TextureMinLod),
+            self.get_parameter_f(TextureProp::TextureMaxLod),
+        )
+    }
+
+    fn get_lod_bias(self) -> f32 {
+        self.get_parameter_f(TextureProp::TextureLodBias)
+    }
+
+    fn get_anisotropy(self) -> f32 {
+        self.get_parameter_f(TextureProp::TextureMaxAnisotropy)
+    }
+}
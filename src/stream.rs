@@ -0,0 +1,248 @@
+use super::{
+    bind_buffer, buffer_data_ptr, buffer_storage_uninit, get_version, has_extension,
+    map_buffer_range, Buffer, BufferKind, BufferStorageFlags, Fence, MapAccess, MapWrite,
+    MappedBuffer, OwnedBuffer, Pod, Usage, WaitResult,
+};
+use std::{
+    collections::VecDeque,
+    ops::{Deref, DerefMut},
+    ptr::null,
+    time::Duration,
+};
+
+enum Storage {
+    /// Immutable storage that stays mapped for the lifetime of the buffer
+    Persistent(*mut u8),
+    /// No `ARB_buffer_storage`, the buffer is orphaned whenever it fills up
+    Orphaning,
+}
+
+/// A ring buffer for data that is re-uploaded every frame.
+///
+/// Positions are tracked as if the ring never wrapped, a fence inserted by
+/// [`StreamBuffer::end_frame`] guards everything written since the previous one.
+pub struct StreamBuffer {
+    buffer: OwnedBuffer,
+    target: BufferKind,
+    size: usize,
+    storage: Storage,
+    head: usize,
+    frame_start: usize,
+    fences: VecDeque<(usize, Fence)>,
+}
+
+impl StreamBuffer {
+    /// Allocates `size` bytes, persistently mapped when `ARB_buffer_storage` is available
+    pub fn new(target: BufferKind, size: usize) -> Self {
+        let persistent = get_version() >= (4, 4) || has_extension("GL_ARB_buffer_storage");
+        Self::with_storage(target, size, persistent)
+    }
+
+    /// Like [`StreamBuffer::new`] but always orphans with `buffer_data` instead of mapping
+    /// persistently
+    pub fn new_orphaning(target: BufferKind, size: usize) -> Self {
+        Self::with_storage(target, size, false)
+    }
+
+    fn with_storage(target: BufferKind, size: usize, persistent: bool) -> Self {
+        assert!(size > 0, "stream buffer can't be empty");
+        let buffer = OwnedBuffer::new();
+        bind_buffer(target, *buffer);
+
+        let storage = if persistent {
+            let flags = BufferStorageFlags::MapWrite
+                | BufferStorageFlags::MapPersistent
+                | BufferStorageFlags::MapCoherent;
+            buffer_storage_uninit(target, size, flags);
+            let access = MapAccess::Write | MapAccess::Persistent | MapAccess::Coherent;
+            let ptr = unsafe {
+                gl!(MapBufferRange(
+                    target as u32,
+                    0,
+                    size as isize,
+                    access.bits()
+                ))
+            } as *mut u8;
+            assert!(!ptr.is_null(), "failed to persistently map stream buffer");
+            Storage::Persistent(ptr)
+        } else {
+            unsafe { buffer_data_ptr(target, size as isize, null(), Usage::StreamDraw) };
+            Storage::Orphaning
+        };
+
+        Self {
+            buffer,
+            target,
+            size,
+            storage,
+            head: 0,
+            frame_start: 0,
+            fences: VecDeque::new(),
+        }
+    }
+
+    pub fn buffer(&self) -> Buffer {
+        *self.buffer
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_persistent(&self) -> bool {
+        matches!(self.storage, Storage::Persistent(_))
+    }
+
+    /// Reserves `len` elements for this frame, binding the buffer to its target.
+    ///
+    /// Blocks if the region is still being read by the GPU. Panics if a single frame allocates
+    /// more than the whole buffer, or if waiting for the GPU fails.
+    pub fn allocate<T: Pod>(&mut self, len: usize) -> StreamSlice<'_, T> {
+        let bytes = len
+            .checked_mul(std::mem::size_of::<T>())
+            .expect("stream allocation is too large");
+        assert!(
+            bytes > 0 && bytes <= self.size,
+            "can't allocate {} bytes from a {} byte stream buffer",
+            bytes,
+            self.size
+        );
+
+        let mut start = align_up(self.head, std::mem::align_of::<T>());
+        if start % self.size + bytes > self.size {
+            start = align_up(start, self.size);
+        }
+        let end = start + bytes;
+        if self.head == self.frame_start {
+            // nothing written this frame yet, so any padding skipped above isn't part of it
+            self.frame_start = start;
+            self.head = start;
+        }
+
+        bind_buffer(self.target, *self.buffer);
+        match self.storage {
+            Storage::Persistent(ptr) => {
+                // everything before `reusable` may be overwritten by this allocation
+                let reusable = end.saturating_sub(self.size);
+                assert!(
+                    reusable <= self.frame_start,
+                    "a single frame allocated more than the whole stream buffer"
+                );
+                while let Some((region_start, fence)) = self.fences.front() {
+                    if *region_start >= reusable {
+                        break;
+                    }
+                    let mut result = WaitResult::TimeoutExpired;
+                    while result == WaitResult::TimeoutExpired {
+                        result = fence.client_wait(true, Duration::from_secs(1));
+                    }
+                    // the GPU may still be reading the region, so it can't be handed out
+                    assert!(
+                        result.is_signaled(),
+                        "waiting on a stream buffer fence failed: {:?}",
+                        result
+                    );
+                    self.fences.pop_front();
+                }
+
+                self.head = end;
+                let data = unsafe { ptr.add(start % self.size) } as *mut T;
+                assert!(data.is_aligned(), "stream buffer mapping is misaligned");
+                StreamSlice {
+                    offset: start % self.size,
+                    ptr: data,
+                    len,
+                    _mapping: None,
+                }
+            }
+            Storage::Orphaning => {
+                if self.head != 0 && start / self.size != (self.head - 1) / self.size {
+                    // the previous storage may still be in use, let the driver hand out new one
+                    unsafe {
+                        buffer_data_ptr(self.target, self.size as isize, null(), Usage::StreamDraw)
+                    };
+                }
+
+                self.head = end;
+                let access =
+                    MapAccess::Write | MapAccess::InvalidateRange | MapAccess::Unsynchronized;
+                let offset = start % self.size;
+                let mut mapping = map_buffer_range::<T, MapWrite>(
+                    self.target,
+                    &mut self.buffer,
+                    offset,
+                    len,
+                    access,
+                )
+                .expect("failed to map stream buffer");
+                StreamSlice {
+                    offset,
+                    ptr: mapping.as_mut_ptr(),
+                    len,
+                    _mapping: Some(mapping),
+                }
+            }
+        }
+    }
+
+    /// Copies `data` into a fresh allocation, returning its offset in bytes
+    pub fn write<T: Pod>(&mut self, data: &[T]) -> usize {
+        let mut slice = self.allocate::<T>(data.len());
+        slice.copy_from_slice(data);
+        slice.offset()
+    }
+
+    /// Call once all draws using this frame's allocations have been submitted
+    pub fn end_frame(&mut self) {
+        if self.head == self.frame_start {
+            return;
+        }
+        if self.is_persistent() {
+            self.fences.push_back((self.frame_start, Fence::new()));
+        }
+        self.frame_start = self.head;
+    }
+}
+
+impl Drop for StreamBuffer {
+    fn drop(&mut self) {
+        if self.is_persistent() {
+            bind_buffer(self.target, *self.buffer);
+            unsafe { gl!(UnmapBuffer(self.target as u32)) };
+        }
+    }
+}
+
+fn align_up(value: usize, align: usize) -> usize {
+    value.div_ceil(align) * align
+}
+
+/// A sub-allocation of a [`StreamBuffer`], derefs to the elements to write
+pub struct StreamSlice<'a, T: Pod> {
+    offset: usize,
+    ptr: *mut T,
+    len: usize,
+    /// Unmapped on drop when orphaning
+    _mapping: Option<MappedBuffer<'a, T, MapWrite>>,
+}
+
+impl<'a, T: Pod> StreamSlice<'a, T> {
+    /// In bytes from the start of the buffer, e.g. for `vertex_attrib_pointer`
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl<'a, T: Pod> Deref for StreamSlice<'a, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+impl<'a, T: Pod> DerefMut for StreamSlice<'a, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+}
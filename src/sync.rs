@@ -0,0 +1,100 @@
+use std::{convert::TryFrom, time::Duration};
+
+gl_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum WaitResult {
+        AlreadySignaled = rgl::ALREADY_SIGNALED,
+        ConditionSatisfied = rgl::CONDITION_SATISFIED,
+        TimeoutExpired = rgl::TIMEOUT_EXPIRED,
+        WaitFailed = rgl::WAIT_FAILED,
+    }
+}
+
+impl WaitResult {
+    /// `AlreadySignaled` or `ConditionSatisfied`
+    pub fn is_signaled(self) -> bool {
+        matches!(self, Self::AlreadySignaled | Self::ConditionSatisfied)
+    }
+}
+
+/// A sync object that becomes signaled once the GPU has finished every command issued before
+/// it, deleted when dropped
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Fence(rgl::types::GLsync);
+
+impl Fence {
+    /// Inserts a fence after all previously issued commands
+    pub fn new() -> Self {
+        fence_sync()
+    }
+
+    /// Blocks the CPU until the fence is signaled or `timeout` expires.
+    ///
+    /// With `flush` set, pending commands are flushed first so the wait can't deadlock.
+    pub fn client_wait(&self, flush: bool, timeout: Duration) -> WaitResult {
+        client_wait_sync(self, flush, timeout)
+    }
+
+    /// Makes the GPU wait for the fence before executing further commands, without blocking
+    pub fn wait(&self) {
+        wait_sync(self)
+    }
+
+    /// Checks whether the fence has been signaled without blocking
+    pub fn is_signaled(&self) -> bool {
+        let mut status: i32 = 0;
+        unsafe {
+            gl!(GetSynciv(
+                self.0,
+                rgl::SYNC_STATUS,
+                1,
+                std::ptr::null_mut(),
+                &mut status
+            ))
+        };
+        status as u32 == rgl::SIGNALED
+    }
+
+    pub fn as_raw(&self) -> rgl::types::GLsync {
+        self.0
+    }
+}
+
+impl Default for Fence {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Fence {
+    fn drop(&mut self) {
+        unsafe { gl!(DeleteSync(self.0)) }
+    }
+}
+
+pub fn fence_sync() -> Fence {
+    let sync = unsafe { gl!(FenceSync(rgl::SYNC_GPU_COMMANDS_COMPLETE, 0)) };
+    assert!(!sync.is_null(), "failed to create fence sync");
+    Fence(sync)
+}
+
+/// Results the driver shouldn't return are reported as [`WaitResult::WaitFailed`]
+pub fn client_wait_sync(fence: &Fence, flush: bool, timeout: Duration) -> WaitResult {
+    let flags = if flush {
+        rgl::SYNC_FLUSH_COMMANDS_BIT
+    } else {
+        0
+    };
+    let timeout = u64::try_from(timeout.as_nanos()).unwrap_or(u64::MAX);
+    let result = unsafe { gl!(ClientWaitSync(fence.0, flags, timeout)) };
+    WaitResult::try_from(result).unwrap_or(WaitResult::WaitFailed)
+}
+
+pub fn wait_sync(fence: &Fence) {
+    unsafe { gl!(WaitSync(fence.0, 0, rgl::TIMEOUT_IGNORED)) }
+}
+
+/// Deletes `fence` right away, same as dropping it
+pub fn delete_sync(fence: Fence) {
+    drop(fence)
+}
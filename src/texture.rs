@@ -1,48 +1,1210 @@
-#[repr(u32)]
-#[derive(Debug, Clone, Copy, PartialEq, Hash)]
-pub enum TextureTarget {
-    Texture1D = gl::TEXTURE_1D,
-    Texture1DArray = gl::TEXTURE_1D_ARRAY,
-    Texture2D = gl::TEXTURE_2D,
-    Texture2DArray = gl::TEXTURE_2D_ARRAY,
-    Texture2DMultiSample = gl::TEXTURE_2D_MULTISAMPLE,
-    Texture2DMultiSampleArray = gl::TEXTURE_2D_MULTISAMPLE_ARRAY,
-    Texture3D = gl::TEXTURE_3D,
-    TextureCubeMap = gl::TEXTURE_CUBE_MAP,
-    TextureCubeMapArray = gl::TEXTURE_CUBE_MAP_ARRAY,
-    TextureRectangle = gl::TEXTURE_RECTANGLE,
-}
-
-#[repr(u32)]
-#[derive(Debug, Clone, Copy, PartialEq, Hash)]
-pub enum TextureProp {
-    DepthStencilTextureMode = gl::DEPTH_STENCIL_TEXTURE_MODE,
-    TextureBaseLevel = gl::TEXTURE_BASE_LEVEL,
-    TextureCompareFunc = gl::TEXTURE_COMPARE_FUNC,
-    TextureCompareMode = gl::TEXTURE_COMPARE_MODE,
-    TextureLodBias = gl::TEXTURE_LOD_BIAS,
-    TextureMinFilter = gl::TEXTURE_MIN_FILTER,
-    TextureMagFilter = gl::TEXTURE_MAG_FILTER,
-    TextureMinLod = gl::TEXTURE_MIN_LOD,
-    TextureMaxLod = gl::TEXTURE_MAX_LOD,
-    TextureMaxLevel = gl::TEXTURE_MAX_LEVEL,
-    TextureSwizzleR = gl::TEXTURE_SWIZZLE_R,
-    TextureSwizzleG = gl::TEXTURE_SWIZZLE_G,
-    TextureSwizzleB = gl::TEXTURE_SWIZZLE_B,
-    TextureSwizzleA = gl::TEXTURE_SWIZZLE_A,
-    TextureWrapS = gl::TEXTURE_WRAP_S,
-    TextureWrapT = gl::TEXTURE_WRAP_T,
-    TextureWrapR = gl::TEXTURE_WRAP_R,
-    // TextureBorderColor = gl::TEXTURE_BORDER_COLOR,
-    // TextureSwizzleRgba = gl::TEXTURE_SWIZZLE_RGBA,
-}
-
-#[repr(u32)]
-#[derive(Debug, Clone, Copy, PartialEq, Hash)]
-pub enum TextureParam {
-    MirroredRepeat = gl::MIRRORED_REPEAT,
+use super::{SamplerParameters, Swizzle, UnknownEnumValue, TEXTURE_MAX_ANISOTROPY};
+use bytemuck::Pod;
+use shrinkwraprs::Shrinkwrap;
+use std::convert::TryFrom;
+
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Shrinkwrap)]
+pub struct Texture(u32);
+
+impl Texture {
+    pub const NONE: Self = Self(0);
+}
+
+/// A [`Texture`] that is deleted when dropped
+#[derive(Debug, PartialEq, Eq, Hash, Shrinkwrap)]
+pub struct OwnedTexture(Texture);
+
+impl OwnedTexture {
+    pub fn new() -> Self {
+        Self(gen_texture())
+    }
+
+    /// Takes ownership of `texture`, it will be deleted when the returned value is dropped
+    pub fn from_raw(texture: Texture) -> Self {
+        Self(texture)
+    }
+
+    pub fn handle(&self) -> Texture {
+        self.0
+    }
+
+    /// Releases ownership without deleting, the caller is responsible for [`delete_texture`]
+    pub fn into_raw(self) -> Texture {
+        let texture = self.0;
+        std::mem::forget(self);
+        texture
+    }
+}
+
+impl Default for OwnedTexture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for OwnedTexture {
+    fn drop(&mut self) {
+        delete_texture(self.0)
+    }
+}
+
+gl_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Hash)]
+    pub enum TextureTarget {
+        Texture1D = gl::TEXTURE_1D,
+        Texture1DArray = gl::TEXTURE_1D_ARRAY,
+        Texture2D = gl::TEXTURE_2D,
+        Texture2DArray = gl::TEXTURE_2D_ARRAY,
+        Texture2DMultiSample = gl::TEXTURE_2D_MULTISAMPLE,
+        Texture2DMultiSampleArray = gl::TEXTURE_2D_MULTISAMPLE_ARRAY,
+        Texture3D = gl::TEXTURE_3D,
+        TextureCubeMap = gl::TEXTURE_CUBE_MAP,
+        TextureCubeMapArray = gl::TEXTURE_CUBE_MAP_ARRAY,
+        TextureRectangle = gl::TEXTURE_RECTANGLE,
+    }
+}
+
+gl_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Hash)]
+    pub enum TextureProp {
+        DepthStencilTextureMode = gl::DEPTH_STENCIL_TEXTURE_MODE,
+        TextureBaseLevel = gl::TEXTURE_BASE_LEVEL,
+        TextureCompareFunc = gl::TEXTURE_COMPARE_FUNC,
+        TextureCompareMode = gl::TEXTURE_COMPARE_MODE,
+        TextureLodBias = gl::TEXTURE_LOD_BIAS,
+        TextureMinFilter = gl::TEXTURE_MIN_FILTER,
+        TextureMagFilter = gl::TEXTURE_MAG_FILTER,
+        TextureMinLod = gl::TEXTURE_MIN_LOD,
+        TextureMaxLod = gl::TEXTURE_MAX_LOD,
+        TextureMaxLevel = gl::TEXTURE_MAX_LEVEL,
+        TextureSwizzleR = gl::TEXTURE_SWIZZLE_R,
+        TextureSwizzleG = gl::TEXTURE_SWIZZLE_G,
+        TextureSwizzleB = gl::TEXTURE_SWIZZLE_B,
+        TextureSwizzleA = gl::TEXTURE_SWIZZLE_A,
+        TextureWrapS = gl::TEXTURE_WRAP_S,
+        TextureWrapT = gl::TEXTURE_WRAP_T,
+        TextureWrapR = gl::TEXTURE_WRAP_R,
+        TextureBorderColor = gl::TEXTURE_BORDER_COLOR,
+        TextureSwizzleRgba = gl::TEXTURE_SWIZZLE_RGBA,
+        TextureMaxAnisotropy = TEXTURE_MAX_ANISOTROPY,
+    }
+}
+
+impl TextureProp {
+    /// How many values GL reads or writes for the vector (`*v`) variants
+    pub fn components(self) -> usize {
+        match self {
+            TextureProp::TextureBorderColor | TextureProp::TextureSwizzleRgba => 4,
+            _ => 1,
+        }
+    }
+
+    pub(crate) fn assert_components(self, len: usize) {
+        assert_eq!(
+            len,
+            self.components(),
+            "{:?} takes {} values",
+            self,
+            self.components()
+        );
+    }
+}
+
+gl_enum! {
+    #[deprecated(note = "use `WrapMode` with `SamplerParameters::set_wrap` instead")]
+    #[derive(Debug, Clone, Copy, PartialEq, Hash)]
+    pub enum TextureParam {
+        MirroredRepeat = gl::MIRRORED_REPEAT,
+    }
+}
+
+gl_enum! {
+    /// Sized internal formats, as required by immutable texture storage
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum InternalFormat {
+        R8 = gl::R8,
+        R8Snorm = gl::R8_SNORM,
+        R16 = gl::R16,
+        R16Snorm = gl::R16_SNORM,
+        R16F = gl::R16F,
+        R32F = gl::R32F,
+        R8I = gl::R8I,
+        R8UI = gl::R8UI,
+        R16I = gl::R16I,
+        R16UI = gl::R16UI,
+        R32I = gl::R32I,
+        R32UI = gl::R32UI,
+        RG8 = gl::RG8,
+        RG8Snorm = gl::RG8_SNORM,
+        RG16 = gl::RG16,
+        RG16Snorm = gl::RG16_SNORM,
+        RG16F = gl::RG16F,
+        RG32F = gl::RG32F,
+        RG8I = gl::RG8I,
+        RG8UI = gl::RG8UI,
+        RG16I = gl::RG16I,
+        RG16UI = gl::RG16UI,
+        RG32I = gl::RG32I,
+        RG32UI = gl::RG32UI,
+        RGB8 = gl::RGB8,
+        RGB8Snorm = gl::RGB8_SNORM,
+        RGB16F = gl::RGB16F,
+        RGB32F = gl::RGB32F,
+        RGB8I = gl::RGB8I,
+        RGB8UI = gl::RGB8UI,
+        RGB32I = gl::RGB32I,
+        RGB32UI = gl::RGB32UI,
+        SRGB8 = gl::SRGB8,
+        R11FG11FB10F = gl::R11F_G11F_B10F,
+        RGB9E5 = gl::RGB9_E5,
+        RGBA8 = gl::RGBA8,
+        RGBA8Snorm = gl::RGBA8_SNORM,
+        RGBA16 = gl::RGBA16,
+        RGBA16F = gl::RGBA16F,
+        RGBA32F = gl::RGBA32F,
+        RGBA8I = gl::RGBA8I,
+        RGBA8UI = gl::RGBA8UI,
+        RGBA16I = gl::RGBA16I,
+        RGBA16UI = gl::RGBA16UI,
+        RGBA32I = gl::RGBA32I,
+        RGBA32UI = gl::RGBA32UI,
+        SRGB8Alpha8 = gl::SRGB8_ALPHA8,
+        RGB10A2 = gl::RGB10_A2,
+        RGB10A2UI = gl::RGB10_A2UI,
+        DepthComponent16 = gl::DEPTH_COMPONENT16,
+        DepthComponent24 = gl::DEPTH_COMPONENT24,
+        DepthComponent32F = gl::DEPTH_COMPONENT32F,
+        Depth24Stencil8 = gl::DEPTH24_STENCIL8,
+        Depth32FStencil8 = gl::DEPTH32F_STENCIL8,
+        StencilIndex8 = gl::STENCIL_INDEX8,
+    }
+}
+
+gl_enum! {
+    /// The targets a single 2D image can be specified for, cube maps take one per face
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum ImageTarget2D {
+        Texture2D = gl::TEXTURE_2D,
+        Texture1DArray = gl::TEXTURE_1D_ARRAY,
+        TextureRectangle = gl::TEXTURE_RECTANGLE,
+        CubeMapPositiveX = gl::TEXTURE_CUBE_MAP_POSITIVE_X,
+        CubeMapNegativeX = gl::TEXTURE_CUBE_MAP_NEGATIVE_X,
+        CubeMapPositiveY = gl::TEXTURE_CUBE_MAP_POSITIVE_Y,
+        CubeMapNegativeY = gl::TEXTURE_CUBE_MAP_NEGATIVE_Y,
+        CubeMapPositiveZ = gl::TEXTURE_CUBE_MAP_POSITIVE_Z,
+        CubeMapNegativeZ = gl::TEXTURE_CUBE_MAP_NEGATIVE_Z,
+    }
+}
+
+gl_enum! {
+    /// The targets a 3D image can be specified for, array layers and cube map faces are its depth
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum ImageTarget3D {
+        Texture3D = gl::TEXTURE_3D,
+        Texture2DArray = gl::TEXTURE_2D_ARRAY,
+        TextureCubeMapArray = gl::TEXTURE_CUBE_MAP_ARRAY,
+    }
+}
+
+gl_enum! {
+    /// The components of the pixels passed to `tex_image_*`
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum PixelFormat {
+        Red = gl::RED,
+        RG = gl::RG,
+        RGB = gl::RGB,
+        BGR = gl::BGR,
+        RGBA = gl::RGBA,
+        BGRA = gl::BGRA,
+        RedInteger = gl::RED_INTEGER,
+        RGInteger = gl::RG_INTEGER,
+        RGBInteger = gl::RGB_INTEGER,
+        BGRInteger = gl::BGR_INTEGER,
+        RGBAInteger = gl::RGBA_INTEGER,
+        BGRAInteger = gl::BGRA_INTEGER,
+        StencilIndex = gl::STENCIL_INDEX,
+        DepthComponent = gl::DEPTH_COMPONENT,
+        DepthStencil = gl::DEPTH_STENCIL,
+    }
+}
+
+impl PixelFormat {
+    pub fn components(self) -> usize {
+        match self {
+            PixelFormat::Red
+            | PixelFormat::RedInteger
+            | PixelFormat::StencilIndex
+            | PixelFormat::DepthComponent => 1,
+            PixelFormat::RG | PixelFormat::RGInteger | PixelFormat::DepthStencil => 2,
+            PixelFormat::RGB
+            | PixelFormat::BGR
+            | PixelFormat::RGBInteger
+            | PixelFormat::BGRInteger => 3,
+            PixelFormat::RGBA
+            | PixelFormat::BGRA
+            | PixelFormat::RGBAInteger
+            | PixelFormat::BGRAInteger => 4,
+        }
+    }
+}
+
+gl_enum! {
+    /// How each pixel passed to `tex_image_*` is stored
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum PixelType {
+        UnsignedByte = gl::UNSIGNED_BYTE,
+        Byte = gl::BYTE,
+        UnsignedShort = gl::UNSIGNED_SHORT,
+        Short = gl::SHORT,
+        UnsignedInt = gl::UNSIGNED_INT,
+        Int = gl::INT,
+        HalfFloat = gl::HALF_FLOAT,
+        Float = gl::FLOAT,
+        UnsignedByte332 = gl::UNSIGNED_BYTE_3_3_2,
+        UnsignedByte233Rev = gl::UNSIGNED_BYTE_2_3_3_REV,
+        UnsignedShort565 = gl::UNSIGNED_SHORT_5_6_5,
+        UnsignedShort565Rev = gl::UNSIGNED_SHORT_5_6_5_REV,
+        UnsignedShort4444 = gl::UNSIGNED_SHORT_4_4_4_4,
+        UnsignedShort4444Rev = gl::UNSIGNED_SHORT_4_4_4_4_REV,
+        UnsignedShort5551 = gl::UNSIGNED_SHORT_5_5_5_1,
+        UnsignedShort1555Rev = gl::UNSIGNED_SHORT_1_5_5_5_REV,
+        UnsignedInt8888 = gl::UNSIGNED_INT_8_8_8_8,
+        UnsignedInt8888Rev = gl::UNSIGNED_INT_8_8_8_8_REV,
+        UnsignedInt1010102 = gl::UNSIGNED_INT_10_10_10_2,
+        UnsignedInt2101010Rev = gl::UNSIGNED_INT_2_10_10_10_REV,
+        UnsignedInt248 = gl::UNSIGNED_INT_24_8,
+        UnsignedInt10F11F11FRev = gl::UNSIGNED_INT_10F_11F_11F_REV,
+        UnsignedInt5999Rev = gl::UNSIGNED_INT_5_9_9_9_REV,
+        Float32UnsignedInt248Rev = gl::FLOAT_32_UNSIGNED_INT_24_8_REV,
+    }
+}
+
+impl PixelType {
+    /// In bytes, packed types are the size of a whole pixel rather than one component
+    pub fn size(self) -> usize {
+        match self {
+            PixelType::UnsignedByte
+            | PixelType::Byte
+            | PixelType::UnsignedByte332
+            | PixelType::UnsignedByte233Rev => 1,
+            PixelType::UnsignedShort
+            | PixelType::Short
+            | PixelType::HalfFloat
+            | PixelType::UnsignedShort565
+            | PixelType::UnsignedShort565Rev
+            | PixelType::UnsignedShort4444
+            | PixelType::UnsignedShort4444Rev
+            | PixelType::UnsignedShort5551
+            | PixelType::UnsignedShort1555Rev => 2,
+            PixelType::UnsignedInt
+            | PixelType::Int
+            | PixelType::Float
+            | PixelType::UnsignedInt8888
+            | PixelType::UnsignedInt8888Rev
+            | PixelType::UnsignedInt1010102
+            | PixelType::UnsignedInt2101010Rev
+            | PixelType::UnsignedInt248
+            | PixelType::UnsignedInt10F11F11FRev
+            | PixelType::UnsignedInt5999Rev => 4,
+            PixelType::Float32UnsignedInt248Rev => 8,
+        }
+    }
+
+    /// The number of components a packed type holds, `None` if every component is stored
+    /// separately
+    pub fn packed_components(self) -> Option<usize> {
+        match self {
+            PixelType::UnsignedByte332
+            | PixelType::UnsignedByte233Rev
+            | PixelType::UnsignedShort565
+            | PixelType::UnsignedShort565Rev
+            | PixelType::UnsignedInt10F11F11FRev
+            | PixelType::UnsignedInt5999Rev => Some(3),
+            PixelType::UnsignedShort4444
+            | PixelType::UnsignedShort4444Rev
+            | PixelType::UnsignedShort5551
+            | PixelType::UnsignedShort1555Rev
+            | PixelType::UnsignedInt8888
+            | PixelType::UnsignedInt8888Rev
+            | PixelType::UnsignedInt1010102
+            | PixelType::UnsignedInt2101010Rev => Some(4),
+            PixelType::UnsignedInt248 | PixelType::Float32UnsignedInt248Rev => Some(2),
+            _ => None,
+        }
+    }
+}
+
+/// Bytes per pixel of `format` stored as `ty`, panics if GL doesn't accept the combination
+pub fn pixel_size(format: PixelFormat, ty: PixelType) -> usize {
+    let components = format.components();
+    match ty.packed_components() {
+        Some(packed) => {
+            assert!(
+                packed == components,
+                "{:?} pixels can't be stored as {:?}",
+                format,
+                ty
+            );
+            ty.size()
+        }
+        None => {
+            assert!(
+                format != PixelFormat::DepthStencil,
+                "DepthStencil pixels need a packed type, got {:?}",
+                ty
+            );
+            components * ty.size()
+        }
+    }
+}
+
+fn get_unpack(pname: u32) -> usize {
+    let mut value: i32 = 0;
+    unsafe { gl!(GetIntegerv(pname, &mut value)) };
+    value.max(0) as usize
+}
+
+/// Minimum number of bytes `tex_image_*` reads for a `width` x `height` x `depth` image under the
+/// current `GL_UNPACK_*` state. `depth` is `None` for 1D and 2D images, which ignore
+/// `GL_UNPACK_IMAGE_HEIGHT` and `GL_UNPACK_SKIP_IMAGES`.
+///
+/// Panics if a pixel unpack buffer is bound, GL would read from it instead of `data`.
+fn image_size(
+    width: i32,
+    height: i32,
+    depth: Option<i32>,
+    format: PixelFormat,
+    ty: PixelType,
+) -> usize {
+    let three_d = depth.is_some();
+    let depth_or_1 = depth.unwrap_or(1);
+    assert!(
+        width >= 0 && height >= 0 && depth_or_1 >= 0,
+        "image size can't be negative, got {}x{}x{}",
+        width,
+        height,
+        depth_or_1
+    );
+    assert!(
+        get_unpack(gl::PIXEL_UNPACK_BUFFER_BINDING) == 0,
+        "can't upload from client memory while a PixelUnpack buffer is bound"
+    );
+    let pixel = pixel_size(format, ty);
+    let (width, height, depth) = (width as usize, height as usize, depth_or_1 as usize);
+    if width == 0 || height == 0 || depth == 0 {
+        return 0;
+    }
+
+    let row_length = match get_unpack(gl::UNPACK_ROW_LENGTH) {
+        0 => width,
+        row_length => row_length,
+    };
+    let alignment = get_unpack(gl::UNPACK_ALIGNMENT).max(1);
+    let stride = (row_length * pixel).next_multiple_of(alignment);
+    let mut skip =
+        get_unpack(gl::UNPACK_SKIP_PIXELS) * pixel + get_unpack(gl::UNPACK_SKIP_ROWS) * stride;
+    let mut image_stride = stride * height;
+    if three_d {
+        let image_height = get_unpack(gl::UNPACK_IMAGE_HEIGHT);
+        if image_height != 0 {
+            image_stride = stride * image_height;
+        }
+        skip += get_unpack(gl::UNPACK_SKIP_IMAGES) * image_stride;
+    }
+    // the last row doesn't need padding
+    skip + image_stride * (depth - 1) + stride * (height - 1) + width * pixel
+}
+
+fn image_bytes<T: Pod>(
+    data: &[T],
+    width: i32,
+    height: i32,
+    depth: Option<i32>,
+    format: PixelFormat,
+    ty: PixelType,
+) -> &[u8] {
+    let bytes: &[u8] = bytemuck::cast_slice(data);
+    let expected = image_size(width, height, depth, format, ty);
+    assert!(
+        bytes.len() >= expected,
+        "a {}x{}x{} {:?}/{:?} image needs {} bytes, got {}",
+        width,
+        height,
+        depth.unwrap_or(1),
+        format,
+        ty,
+        expected,
+        bytes.len()
+    );
+    bytes
+}
+
+pub fn gen_textures<const N: usize>() -> [Texture; N] {
+    assert!(N < u32::MAX as usize);
+    let mut textures = [Texture(0); N];
+    unsafe { gl!(GenTextures(N as i32, textures.as_mut_ptr() as *mut u32)) };
+    textures
+}
+
+pub fn gen_texture() -> Texture {
+    gen_textures::<1>()[0]
+}
+
+/// Deletes every texture in `textures`, skipping [`Texture::NONE`]
+pub fn delete_textures(textures: &[Texture]) {
+    let names: Vec<u32> = textures
+        .iter()
+        .filter(|t| **t != Texture::NONE)
+        .map(|t| t.0)
+        .collect();
+    if names.is_empty() {
+        return;
+    }
+    assert!(names.len() < i32::MAX as usize);
+    unsafe { gl!(DeleteTextures(names.len() as i32, names.as_ptr())) }
+}
+
+pub fn delete_texture(texture: Texture) {
+    delete_textures(&[texture])
+}
+
+/// Binds `texture` to `target` of the active texture unit
+pub fn bind_texture(target: TextureTarget, texture: Texture) {
+    unsafe { gl!(BindTexture(target as u32, texture.0)) }
+}
+
+/// Selects which texture unit `bind_texture` affects, `unit` counts from 0 rather than
+/// `GL_TEXTURE0`
+pub fn active_texture(unit: u32) {
+    unsafe { gl!(ActiveTexture(gl::TEXTURE0 + unit)) }
 }
 
 pub fn tex_parameter_i(target: TextureTarget, prop: TextureProp, param: i32) {
-    unsafe { gl::TexParameteri(target as u32, prop as u32, param) }
+    unsafe { gl!(TexParameteri(target as u32, prop as u32, param)) }
+}
+
+pub fn tex_parameter_f(target: TextureTarget, prop: TextureProp, param: f32) {
+    unsafe { gl!(TexParameterf(target as u32, prop as u32, param)) }
+}
+
+/// `params` must hold [`TextureProp::components`] values
+pub fn tex_parameter_iv(target: TextureTarget, prop: TextureProp, params: &[i32]) {
+    prop.assert_components(params.len());
+    unsafe { gl!(TexParameteriv(target as u32, prop as u32, params.as_ptr())) }
+}
+
+/// `params` must hold [`TextureProp::components`] values
+pub fn tex_parameter_fv(target: TextureTarget, prop: TextureProp, params: &[f32]) {
+    prop.assert_components(params.len());
+    unsafe { gl!(TexParameterfv(target as u32, prop as u32, params.as_ptr())) }
+}
+
+/// Like [`tex_parameter_iv`] but the border color of integer textures isn't normalized
+pub fn tex_parameter_i_iv(target: TextureTarget, prop: TextureProp, params: &[i32]) {
+    prop.assert_components(params.len());
+    unsafe { gl!(TexParameterIiv(target as u32, prop as u32, params.as_ptr())) }
+}
+
+/// Like [`tex_parameter_i_iv`] for unsigned integer textures
+pub fn tex_parameter_i_uiv(target: TextureTarget, prop: TextureProp, params: &[u32]) {
+    prop.assert_components(params.len());
+    unsafe {
+        gl!(TexParameterIuiv(
+            target as u32,
+            prop as u32,
+            params.as_ptr()
+        ))
+    }
+}
+
+pub fn get_tex_parameter_i(target: TextureTarget, prop: TextureProp) -> i32 {
+    let mut param = [0];
+    get_tex_parameter_iv(target, prop, &mut param);
+    param[0]
+}
+
+pub fn get_tex_parameter_f(target: TextureTarget, prop: TextureProp) -> f32 {
+    let mut param = [0.0];
+    get_tex_parameter_fv(target, prop, &mut param);
+    param[0]
+}
+
+/// `params` must hold [`TextureProp::components`] values
+pub fn get_tex_parameter_iv(target: TextureTarget, prop: TextureProp, params: &mut [i32]) {
+    prop.assert_components(params.len());
+    unsafe {
+        gl!(GetTexParameteriv(
+            target as u32,
+            prop as u32,
+            params.as_mut_ptr()
+        ))
+    }
+}
+
+/// `params` must hold [`TextureProp::components`] values
+pub fn get_tex_parameter_fv(target: TextureTarget, prop: TextureProp, params: &mut [f32]) {
+    prop.assert_components(params.len());
+    unsafe {
+        gl!(GetTexParameterfv(
+            target as u32,
+            prop as u32,
+            params.as_mut_ptr()
+        ))
+    }
+}
+
+/// `params` must hold [`TextureProp::components`] values
+pub fn get_tex_parameter_i_iv(target: TextureTarget, prop: TextureProp, params: &mut [i32]) {
+    prop.assert_components(params.len());
+    unsafe {
+        gl!(GetTexParameterIiv(
+            target as u32,
+            prop as u32,
+            params.as_mut_ptr()
+        ))
+    }
+}
+
+/// `params` must hold [`TextureProp::components`] values
+pub fn get_tex_parameter_i_uiv(target: TextureTarget, prop: TextureProp, params: &mut [u32]) {
+    prop.assert_components(params.len());
+    unsafe {
+        gl!(GetTexParameterIuiv(
+            target as u32,
+            prop as u32,
+            params.as_mut_ptr()
+        ))
+    }
+}
+
+/// Acts on the texture bound to the target of the active texture unit
+impl SamplerParameters for TextureTarget {
+    fn parameter_i(self, prop: TextureProp, param: i32) {
+        tex_parameter_i(self, prop, param)
+    }
+
+    fn parameter_f(self, prop: TextureProp, param: f32) {
+        tex_parameter_f(self, prop, param)
+    }
+
+    fn parameter_iv(self, prop: TextureProp, params: &[i32]) {
+        tex_parameter_iv(self, prop, params)
+    }
+
+    fn parameter_fv(self, prop: TextureProp, params: &[f32]) {
+        tex_parameter_fv(self, prop, params)
+    }
+
+    fn parameter_i_iv(self, prop: TextureProp, params: &[i32]) {
+        tex_parameter_i_iv(self, prop, params)
+    }
+
+    fn parameter_i_uiv(self, prop: TextureProp, params: &[u32]) {
+        tex_parameter_i_uiv(self, prop, params)
+    }
+
+    fn get_parameter_iv(self, prop: TextureProp, params: &mut [i32]) {
+        get_tex_parameter_iv(self, prop, params)
+    }
+
+    fn get_parameter_fv(self, prop: TextureProp, params: &mut [f32]) {
+        get_tex_parameter_fv(self, prop, params)
+    }
+
+    fn get_parameter_i_iv(self, prop: TextureProp, params: &mut [i32]) {
+        get_tex_parameter_i_iv(self, prop, params)
+    }
+
+    fn get_parameter_i_uiv(self, prop: TextureProp, params: &mut [u32]) {
+        get_tex_parameter_i_uiv(self, prop, params)
+    }
+}
+
+/// Parameters that belong to the texture itself rather than to how it's sampled, so samplers
+/// don't have them
+impl TextureTarget {
+    /// Where the red, green, blue and alpha components read by the shader come from
+    pub fn set_swizzle(self, swizzle: [Swizzle; 4]) {
+        let swizzle = swizzle.map(|s| s as i32);
+        tex_parameter_iv(self, TextureProp::TextureSwizzleRgba, &swizzle)
+    }
+
+    pub fn get_swizzle(self) -> Result<[Swizzle; 4], UnknownEnumValue> {
+        let mut swizzle = [0; 4];
+        get_tex_parameter_iv(self, TextureProp::TextureSwizzleRgba, &mut swizzle);
+        Ok([
+            Swizzle::try_from(swizzle[0] as u32)?,
+            Swizzle::try_from(swizzle[1] as u32)?,
+            Swizzle::try_from(swizzle[2] as u32)?,
+            Swizzle::try_from(swizzle[3] as u32)?,
+        ])
+    }
+
+    /// Limits which mip levels exist as far as sampling and completeness are concerned
+    pub fn set_level_range(self, base: i32, max: i32) {
+        assert!(
+            0 <= base && base <= max,
+            "mip level range {}..={} is invalid",
+            base,
+            max
+        );
+        tex_parameter_i(self, TextureProp::TextureBaseLevel, base);
+        tex_parameter_i(self, TextureProp::TextureMaxLevel, max);
+    }
+}
+
+/// Specifies mip `level` of the 1D texture bound to `TextureTarget::Texture1D` from `data`
+///
+/// `data` is checked like in [`tex_image_2d`].
+pub fn tex_image_1d<T: Pod>(
+    level: i32,
+    internal_format: InternalFormat,
+    width: i32,
+    format: PixelFormat,
+    ty: PixelType,
+    data: &[T],
+) {
+    let data = image_bytes(data, width, 1, None, format, ty);
+    unsafe {
+        gl!(TexImage1D(
+            gl::TEXTURE_1D,
+            level,
+            internal_format as i32,
+            width,
+            0,
+            format as u32,
+            ty as u32,
+            data.as_ptr() as *const _,
+        ))
+    }
+}
+
+/// Like [`tex_image_1d`] but leaves the contents undefined
+pub fn tex_image_1d_uninit(level: i32, internal_format: InternalFormat, width: i32) {
+    let (format, ty) = uninit_transfer(internal_format);
+    unsafe {
+        gl!(TexImage1D(
+            gl::TEXTURE_1D,
+            level,
+            internal_format as i32,
+            width,
+            0,
+            format as u32,
+            ty as u32,
+            std::ptr::null(),
+        ))
+    }
+}
+
+/// Specifies mip `level` of the image at `target` from `data`
+///
+/// Panics if `data` is too short for the image as laid out by the `GL_UNPACK_*` pixel store
+/// state, or if a pixel unpack buffer is bound. Checking this queries that state, up to seven
+/// `glGetIntegerv` calls per upload.
+#[allow(clippy::too_many_arguments)]
+pub fn tex_image_2d<T: Pod>(
+    target: ImageTarget2D,
+    level: i32,
+    internal_format: InternalFormat,
+    width: i32,
+    height: i32,
+    format: PixelFormat,
+    ty: PixelType,
+    data: &[T],
+) {
+    let data = image_bytes(data, width, height, None, format, ty);
+    unsafe {
+        gl!(TexImage2D(
+            target as u32,
+            level,
+            internal_format as i32,
+            width,
+            height,
+            0,
+            format as u32,
+            ty as u32,
+            data.as_ptr() as *const _,
+        ))
+    }
+}
+
+/// Like [`tex_image_2d`] but leaves the contents undefined, e.g. for render targets
+pub fn tex_image_2d_uninit(
+    target: ImageTarget2D,
+    level: i32,
+    internal_format: InternalFormat,
+    width: i32,
+    height: i32,
+) {
+    let (format, ty) = uninit_transfer(internal_format);
+    unsafe {
+        gl!(TexImage2D(
+            target as u32,
+            level,
+            internal_format as i32,
+            width,
+            height,
+            0,
+            format as u32,
+            ty as u32,
+            std::ptr::null(),
+        ))
+    }
+}
+
+/// Specifies mip `level` of the 3D or array texture bound to `target` from `data`
+///
+/// `data` is checked like in [`tex_image_2d`].
+#[allow(clippy::too_many_arguments)]
+pub fn tex_image_3d<T: Pod>(
+    target: ImageTarget3D,
+    level: i32,
+    internal_format: InternalFormat,
+    width: i32,
+    height: i32,
+    depth: i32,
+    format: PixelFormat,
+    ty: PixelType,
+    data: &[T],
+) {
+    let data = image_bytes(data, width, height, Some(depth), format, ty);
+    unsafe {
+        gl!(TexImage3D(
+            target as u32,
+            level,
+            internal_format as i32,
+            width,
+            height,
+            depth,
+            0,
+            format as u32,
+            ty as u32,
+            data.as_ptr() as *const _,
+        ))
+    }
+}
+
+/// Like [`tex_image_3d`] but leaves the contents undefined
+pub fn tex_image_3d_uninit(
+    target: ImageTarget3D,
+    level: i32,
+    internal_format: InternalFormat,
+    width: i32,
+    height: i32,
+    depth: i32,
+) {
+    let (format, ty) = uninit_transfer(internal_format);
+    unsafe {
+        gl!(TexImage3D(
+            target as u32,
+            level,
+            internal_format as i32,
+            width,
+            height,
+            depth,
+            0,
+            format as u32,
+            ty as u32,
+            std::ptr::null(),
+        ))
+    }
+}
+
+/// Overwrites `width` texels of mip `level` starting at `x`
+///
+/// `data` is checked like in [`tex_image_2d`].
+pub fn tex_sub_image_1d<T: Pod>(
+    level: i32,
+    x: i32,
+    width: i32,
+    format: PixelFormat,
+    ty: PixelType,
+    data: &[T],
+) {
+    let data = image_bytes(data, width, 1, None, format, ty);
+    unsafe {
+        gl!(TexSubImage1D(
+            gl::TEXTURE_1D,
+            level,
+            x,
+            width,
+            format as u32,
+            ty as u32,
+            data.as_ptr() as *const _,
+        ))
+    }
+}
+
+/// Overwrites a `width` x `height` region of mip `level` starting at `(x, y)`
+///
+/// `data` is checked like in [`tex_image_2d`].
+#[allow(clippy::too_many_arguments)]
+pub fn tex_sub_image_2d<T: Pod>(
+    target: ImageTarget2D,
+    level: i32,
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    format: PixelFormat,
+    ty: PixelType,
+    data: &[T],
+) {
+    let data = image_bytes(data, width, height, None, format, ty);
+    unsafe {
+        gl!(TexSubImage2D(
+            target as u32,
+            level,
+            x,
+            y,
+            width,
+            height,
+            format as u32,
+            ty as u32,
+            data.as_ptr() as *const _,
+        ))
+    }
+}
+
+/// Overwrites a `width` x `height` x `depth` region of mip `level` starting at `(x, y, z)`
+///
+/// `data` is checked like in [`tex_image_2d`].
+#[allow(clippy::too_many_arguments)]
+pub fn tex_sub_image_3d<T: Pod>(
+    target: ImageTarget3D,
+    level: i32,
+    x: i32,
+    y: i32,
+    z: i32,
+    width: i32,
+    height: i32,
+    depth: i32,
+    format: PixelFormat,
+    ty: PixelType,
+    data: &[T],
+) {
+    let data = image_bytes(data, width, height, Some(depth), format, ty);
+    unsafe {
+        gl!(TexSubImage3D(
+            target as u32,
+            level,
+            x,
+            y,
+            z,
+            width,
+            height,
+            depth,
+            format as u32,
+            ty as u32,
+            data.as_ptr() as *const _,
+        ))
+    }
+}
+
+/// Generates every mip level below the base level of the texture bound to `target`
+pub fn generate_mipmap(target: TextureTarget) {
+    unsafe { gl!(GenerateMipmap(target as u32)) }
+}
+
+/// Panics unless `levels` is between 1 and the length of the full mip chain of a non-empty
+/// `width` x `height` x `depth` texture
+fn assert_storage_levels(levels: i32, width: i32, height: i32, depth: i32) {
+    assert!(
+        width > 0 && height > 0 && depth > 0,
+        "texture storage can't be empty, got {}x{}x{}",
+        width,
+        height,
+        depth
+    );
+    let max_levels = 32 - (width.max(height).max(depth) as u32).leading_zeros() as i32;
+    assert!(
+        (1..=max_levels).contains(&levels),
+        "{} mip levels requested but a {}x{}x{} texture has 1 to {}",
+        levels,
+        width,
+        height,
+        depth,
+        max_levels
+    );
+}
+
+/// Panics unless `target` has 2D storage that can hold `levels` mip levels of `width` x `height`
+fn assert_storage_2d(target: TextureTarget, levels: i32, width: i32, height: i32) {
+    // the layers of a 1D array don't shrink with each level
+    let mip_height = match target {
+        TextureTarget::Texture2D | TextureTarget::TextureRectangle => height,
+        TextureTarget::Texture1DArray => 1,
+        TextureTarget::TextureCubeMap => {
+            assert!(
+                width == height,
+                "cube map faces must be square, got {}x{}",
+                width,
+                height
+            );
+            height
+        }
+        _ => panic!("{:?} doesn't have 2D storage", target),
+    };
+    assert_storage_levels(levels, width, mip_height, 1);
+    assert!(height > 0, "texture storage can't be empty");
+    assert!(
+        target != TextureTarget::TextureRectangle || levels == 1,
+        "rectangle textures have exactly 1 mip level, got {}",
+        levels
+    );
+}
+
+/// Allocates immutable storage for `levels` mip levels of the texture bound to
+/// `TextureTarget::Texture1D`
+pub fn tex_storage_1d(levels: i32, format: InternalFormat, width: i32) {
+    assert_storage_levels(levels, width, 1, 1);
+    unsafe { gl!(TexStorage1D(gl::TEXTURE_1D, levels, format as u32, width)) }
+}
+
+/// Allocates immutable storage for `levels` mip levels of the 2D, 1D array, rectangle or cube map
+/// texture bound to `target`. Rectangle textures have a single level and cube map faces are
+/// square.
+pub fn tex_storage_2d(
+    target: TextureTarget,
+    levels: i32,
+    format: InternalFormat,
+    width: i32,
+    height: i32,
+) {
+    assert_storage_2d(target, levels, width, height);
+    unsafe {
+        gl!(TexStorage2D(
+            target as u32,
+            levels,
+            format as u32,
+            width,
+            height
+        ))
+    }
+}
+
+/// Allocates immutable storage for `levels` mip levels of the 3D, 2D array or cube map array
+/// texture bound to `target`. For cube map arrays `depth` counts faces, so is a multiple of 6.
+pub fn tex_storage_3d(
+    target: TextureTarget,
+    levels: i32,
+    format: InternalFormat,
+    width: i32,
+    height: i32,
+    depth: i32,
+) {
+    let mip_depth = match target {
+        TextureTarget::Texture3D => depth,
+        TextureTarget::Texture2DArray => 1,
+        TextureTarget::TextureCubeMapArray => {
+            assert!(
+                depth % 6 == 0,
+                "cube map array depth must be a multiple of 6, got {}",
+                depth
+            );
+            1
+        }
+        _ => panic!("{:?} doesn't have 3D storage", target),
+    };
+    assert_storage_levels(levels, width, height, mip_depth);
+    assert!(depth > 0, "texture storage can't be empty");
+    unsafe {
+        gl!(TexStorage3D(
+            target as u32,
+            levels,
+            format as u32,
+            width,
+            height,
+            depth
+        ))
+    }
+}
+
+/// Allocates immutable storage for the texture bound to `TextureTarget::Texture2DMultiSample`.
+///
+/// With `fixed_sample_locations` every texel uses the same sample pattern, which is needed to
+/// mix it with renderbuffers in one framebuffer.
+pub fn tex_storage_2d_multisample(
+    samples: i32,
+    format: InternalFormat,
+    width: i32,
+    height: i32,
+    fixed_sample_locations: bool,
+) {
+    assert!(samples > 0, "multisample storage needs at least 1 sample");
+    assert_storage_levels(1, width, height, 1);
+    unsafe {
+        gl!(TexStorage2DMultisample(
+            gl::TEXTURE_2D_MULTISAMPLE,
+            samples,
+            format as u32,
+            width,
+            height,
+            fixed_sample_locations as u8
+        ))
+    }
+}
+
+/// Like [`tex_storage_2d_multisample`] for the texture bound to
+/// `TextureTarget::Texture2DMultiSampleArray`, with `layers` layers
+pub fn tex_storage_3d_multisample(
+    samples: i32,
+    format: InternalFormat,
+    width: i32,
+    height: i32,
+    layers: i32,
+    fixed_sample_locations: bool,
+) {
+    assert!(samples > 0, "multisample storage needs at least 1 sample");
+    assert_storage_levels(1, width, height, layers);
+    unsafe {
+        gl!(TexStorage3DMultisample(
+            gl::TEXTURE_2D_MULTISAMPLE_ARRAY,
+            samples,
+            format as u32,
+            width,
+            height,
+            layers,
+            fixed_sample_locations as u8
+        ))
+    }
+}
+
+/// Makes `view` share the `levels` mip levels and `layers` layers of `original`'s storage,
+/// seen as `target` with `format`.
+///
+/// `original` must have immutable storage (see [`tex_storage_2d`]) and `view` must come straight
+/// from [`gen_texture`] without ever having been bound. `format` has to be in the same class as
+/// `original`'s, e.g. `RGBA8` and `SRGB8Alpha8`.
+pub fn texture_view(
+    view: Texture,
+    target: TextureTarget,
+    original: Texture,
+    format: InternalFormat,
+    levels: std::ops::Range<u32>,
+    layers: std::ops::Range<u32>,
+) {
+    assert!(!levels.is_empty(), "texture view needs at least 1 level");
+    assert!(!layers.is_empty(), "texture view needs at least 1 layer");
+    assert!(
+        view != Texture::NONE && original != Texture::NONE,
+        "texture views can't involve Texture::NONE"
+    );
+    unsafe {
+        gl!(TextureView(
+            view.0,
+            target as u32,
+            original.0,
+            format as u32,
+            levels.start,
+            levels.end - levels.start,
+            layers.start,
+            layers.end - layers.start
+        ))
+    }
+}
+
+/// A `format` and `type` matching `internal_format`, which GL validates even when no data is
+/// passed
+fn uninit_transfer(internal_format: InternalFormat) -> (PixelFormat, PixelType) {
+    use InternalFormat::*;
+    let format = match internal_format {
+        R8 | R8Snorm | R16 | R16Snorm | R16F | R32F => PixelFormat::Red,
+        R8I | R8UI | R16I | R16UI | R32I | R32UI => PixelFormat::RedInteger,
+        RG8 | RG8Snorm | RG16 | RG16Snorm | RG16F | RG32F => PixelFormat::RG,
+        RG8I | RG8UI | RG16I | RG16UI | RG32I | RG32UI => PixelFormat::RGInteger,
+        RGB8 | RGB8Snorm | RGB16F | RGB32F | SRGB8 | R11FG11FB10F | RGB9E5 => PixelFormat::RGB,
+        RGB8I | RGB8UI | RGB32I | RGB32UI => PixelFormat::RGBInteger,
+        RGBA8 | RGBA8Snorm | RGBA16 | RGBA16F | RGBA32F | SRGB8Alpha8 | RGB10A2 => {
+            PixelFormat::RGBA
+        }
+        RGBA8I | RGBA8UI | RGBA16I | RGBA16UI | RGBA32I | RGBA32UI | RGB10A2UI => {
+            PixelFormat::RGBAInteger
+        }
+        DepthComponent16 | DepthComponent24 | DepthComponent32F => PixelFormat::DepthComponent,
+        Depth24Stencil8 | Depth32FStencil8 => PixelFormat::DepthStencil,
+        StencilIndex8 => PixelFormat::StencilIndex,
+    };
+    let ty = match internal_format {
+        Depth24Stencil8 => PixelType::UnsignedInt248,
+        Depth32FStencil8 => PixelType::Float32UnsignedInt248Rev,
+        _ => PixelType::UnsignedByte,
+    };
+    (format, ty)
+}
+
+/// Creates `N` textures of `target` right away, so they can be used with the `texture_*`
+/// functions without being bound first
+pub fn create_textures<const N: usize>(target: TextureTarget) -> [Texture; N] {
+    assert!(N < u32::MAX as usize);
+    super::assert_dsa_loaded(gl::CreateTextures::is_loaded(), "glCreateTextures");
+    let mut textures = [Texture(0); N];
+    unsafe {
+        gl!(CreateTextures(
+            target as u32,
+            N as i32,
+            textures.as_mut_ptr() as *mut u32
+        ))
+    };
+    textures
+}
+
+pub fn create_texture(target: TextureTarget) -> Texture {
+    create_textures::<1>(target)[0]
+}
+
+pub fn texture_parameter_i(texture: Texture, prop: TextureProp, param: i32) {
+    unsafe { gl!(TextureParameteri(texture.0, prop as u32, param)) }
+}
+
+/// The target `texture` was created with
+fn texture_target(texture: Texture) -> TextureTarget {
+    let mut target: i32 = 0;
+    unsafe {
+        gl!(GetTextureParameteriv(
+            texture.0,
+            gl::TEXTURE_TARGET,
+            &mut target
+        ))
+    };
+    TextureTarget::try_from(target as u32).expect("texture has no known target")
+}
+
+/// Allocates immutable storage for `levels` mip levels of a 2D, 1D array, rectangle or cube map
+/// texture, validated like [`tex_storage_2d`] against the target `texture` was created with
+pub fn texture_storage_2d(
+    texture: Texture,
+    levels: i32,
+    format: InternalFormat,
+    width: i32,
+    height: i32,
+) {
+    assert_storage_2d(texture_target(texture), levels, width, height);
+    unsafe {
+        gl!(TextureStorage2D(
+            texture.0,
+            levels,
+            format as u32,
+            width,
+            height
+        ))
+    }
+}
+
+impl Texture {
+    /// See [`create_texture`]
+    pub fn create(target: TextureTarget) -> Self {
+        create_texture(target)
+    }
+
+    /// See [`texture_parameter_i`]
+    pub fn parameter_i(self, prop: TextureProp, param: i32) {
+        texture_parameter_i(self, prop, param)
+    }
+
+    /// See [`texture_storage_2d`]
+    pub fn storage_2d(self, levels: i32, format: InternalFormat, width: i32, height: i32) {
+        texture_storage_2d(self, levels, format, width, height)
+    }
 }
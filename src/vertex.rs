@@ -0,0 +1,176 @@
+use super::{
+    bind_vertex_array, enable_vertex_attrib_array, vertex_attrib_binding, vertex_attrib_format,
+    vertex_attrib_i_format, vertex_attrib_ipointer, vertex_attrib_l_format, vertex_attrib_lpointer,
+    vertex_attrib_pointer, Type, VertexArray,
+};
+
+#[cfg(feature = "derive")]
+pub use sgl_derive::Vertex;
+
+/// Which `vertex_attrib_*pointer` an attribute is set up with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AttributeKind {
+    /// `vertex_attrib_pointer`, read as floats by the shader (optionally normalized)
+    Float,
+    /// `vertex_attrib_ipointer`, read as `int`/`uint` by the shader
+    Integer,
+    /// `vertex_attrib_lpointer`, read as `double` by the shader
+    Double,
+}
+
+/// A single field of a [`Vertex`]
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
+pub struct VertexAttribute {
+    /// Number of components, 1 to 4
+    pub size: i32,
+    pub ty: Type,
+    pub kind: AttributeKind,
+    pub normalized: bool,
+    /// In bytes from the start of the vertex
+    pub offset: usize,
+}
+
+impl VertexAttribute {
+    /// `dvec3` and `dvec4` take up two attribute locations
+    pub fn locations(&self) -> u32 {
+        if self.kind == AttributeKind::Double && self.size > 2 {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+/// Describes the layout of a vertex struct, usually via `#[derive(Vertex)]`
+pub trait Vertex: Copy {
+    const ATTRIBUTES: &'static [VertexAttribute];
+}
+
+/// A type that can be used as a field of a [`Vertex`]
+pub trait VertexField {
+    const SIZE: i32;
+    const TYPE: Type;
+    const KIND: AttributeKind;
+}
+
+macro_rules! impl_vertex_field {
+    ($($t:ty => $ty:ident, $kind:ident;)*) => {$(
+        impl VertexField for $t {
+            const SIZE: i32 = 1;
+            const TYPE: Type = Type::$ty;
+            const KIND: AttributeKind = AttributeKind::$kind;
+        }
+
+        impl VertexField for [$t; 1] {
+            const SIZE: i32 = 1;
+            const TYPE: Type = Type::$ty;
+            const KIND: AttributeKind = AttributeKind::$kind;
+        }
+
+        impl VertexField for [$t; 2] {
+            const SIZE: i32 = 2;
+            const TYPE: Type = Type::$ty;
+            const KIND: AttributeKind = AttributeKind::$kind;
+        }
+
+        impl VertexField for [$t; 3] {
+            const SIZE: i32 = 3;
+            const TYPE: Type = Type::$ty;
+            const KIND: AttributeKind = AttributeKind::$kind;
+        }
+
+        impl VertexField for [$t; 4] {
+            const SIZE: i32 = 4;
+            const TYPE: Type = Type::$ty;
+            const KIND: AttributeKind = AttributeKind::$kind;
+        }
+    )*};
+}
+
+impl_vertex_field! {
+    i8 => Byte, Integer;
+    u8 => UnsignedByte, Integer;
+    i16 => Short, Integer;
+    u16 => UnsignedShort, Integer;
+    i32 => Int, Integer;
+    u32 => UnsignedInt, Integer;
+    f32 => Float, Float;
+    f64 => Double, Double;
+}
+
+impl VertexArray {
+    /// Binds this vertex array and sets up every attribute of `V` from the currently bound
+    /// `BufferKind::Array` buffer, starting at location 0
+    pub fn apply_layout<V: Vertex>(self) {
+        self.apply_layout_at::<V>(0);
+    }
+
+    /// Like [`VertexArray::apply_layout`] but starting at location `first`, returns the next free
+    /// location so several buffers can be laid out one after another
+    pub fn apply_layout_at<V: Vertex>(self, first: u32) -> u32 {
+        bind_vertex_array(self);
+
+        let stride = std::mem::size_of::<V>() as i32;
+        let mut location = first;
+        for attribute in V::ATTRIBUTES {
+            debug_assert!((1..=4).contains(&attribute.size));
+            enable_vertex_attrib_array(location);
+
+            let offset = attribute.offset as *const _;
+            match attribute.kind {
+                AttributeKind::Float => vertex_attrib_pointer(
+                    location,
+                    attribute.size,
+                    attribute.ty,
+                    attribute.normalized,
+                    stride,
+                    attribute.offset as isize,
+                ),
+                AttributeKind::Integer => unsafe {
+                    vertex_attrib_ipointer(location, attribute.size, attribute.ty, stride, offset)
+                },
+                AttributeKind::Double => unsafe {
+                    vertex_attrib_lpointer(location, attribute.size, stride, offset)
+                },
+            }
+
+            location += attribute.locations();
+        }
+        location
+    }
+
+    /// Like [`VertexArray::apply_layout_at`] but only sets up the format of each attribute and
+    /// points it at binding point `binding`, so buffers can be swapped with `bind_vertex_buffer`
+    /// using a stride of `size_of::<V>()`
+    pub fn apply_format_at<V: Vertex>(self, binding: u32, first: u32) -> u32 {
+        bind_vertex_array(self);
+
+        let mut location = first;
+        for attribute in V::ATTRIBUTES {
+            debug_assert!((1..=4).contains(&attribute.size));
+            enable_vertex_attrib_array(location);
+
+            let offset = attribute.offset as u32;
+            match attribute.kind {
+                AttributeKind::Float => vertex_attrib_format(
+                    location,
+                    attribute.size,
+                    attribute.ty,
+                    attribute.normalized,
+                    offset,
+                ),
+                AttributeKind::Integer => {
+                    vertex_attrib_i_format(location, attribute.size, attribute.ty, offset)
+                }
+                AttributeKind::Double => vertex_attrib_l_format(location, attribute.size, offset),
+            }
+            // both locations of a dvec3/dvec4 read from the same binding
+            for l in location..location + attribute.locations() {
+                vertex_attrib_binding(l, binding);
+            }
+
+            location += attribute.locations();
+        }
+        location
+    }
+}
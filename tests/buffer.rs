@@ -0,0 +1,205 @@
+//! Checks the validation of buffer updates against a stubbed GL loader with a 16 byte buffer
+//! bound.
+
+mod common;
+
+use std::{
+    ffi::c_void,
+    panic::{self, AssertUnwindSafe},
+    ptr::{addr_of_mut, null},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        MutexGuard,
+    },
+};
+
+use enumflags2::BitFlags;
+use sgl::{
+    map_buffer_range, map_buffer_range_raw, Buffer, BufferKind, IndexedBufferKind, MapAccess,
+    MapRead, MapReadWrite, MapWrite, OwnedBuffer,
+};
+
+const BUFFER_SIZE: i64 = 16;
+
+static TRANSFERS: AtomicUsize = AtomicUsize::new(0);
+static MAPS: AtomicUsize = AtomicUsize::new(0);
+static UNMAPS: AtomicUsize = AtomicUsize::new(0);
+static mut STORAGE: [u32; 4] = [0; 4];
+
+extern "system" fn get_buffer_parameter(_: u32, _: u32, data: *mut i64) {
+    unsafe { *data = BUFFER_SIZE };
+}
+
+extern "system" fn get_integer(pname: u32, data: *mut i32) {
+    let value = match pname {
+        0x8A34 => 256, // GL_UNIFORM_BUFFER_OFFSET_ALIGNMENT
+        _ => 0,
+    };
+    unsafe { *data = value };
+}
+
+extern "system" fn bind_buffer_range(_: u32, _: u32, _: u32, _: isize, _: isize) {}
+
+extern "system" fn bind_buffer(_: u32, _: u32) {}
+
+extern "system" fn delete_buffers(_: i32, _: *const u32) {}
+
+extern "system" fn map_range(_: u32, offset: isize, _: isize, _: u32) -> *mut c_void {
+    MAPS.fetch_add(1, Ordering::SeqCst);
+    unsafe { (addr_of_mut!(STORAGE) as *mut u8).add(offset as usize) as *mut c_void }
+}
+
+extern "system" fn unmap_buffer(_: u32) -> u8 {
+    UNMAPS.fetch_add(1, Ordering::SeqCst);
+    1
+}
+
+extern "system" fn buffer_sub_data(_: u32, _: isize, _: isize, _: *const c_void) {
+    TRANSFERS.fetch_add(1, Ordering::SeqCst);
+}
+
+extern "system" fn get_buffer_sub_data(_: u32, _: isize, _: isize, _: *mut c_void) {
+    TRANSFERS.fetch_add(1, Ordering::SeqCst);
+}
+
+fn stubs(name: &str) -> *const c_void {
+    match name {
+        "glGetIntegerv" => get_integer as *const c_void,
+        "glGenBuffers" => common::gen_names as *const c_void,
+        "glDeleteBuffers" => delete_buffers as *const c_void,
+        "glBindBuffer" => bind_buffer as *const c_void,
+        "glMapBufferRange" => map_range as *const c_void,
+        "glUnmapBuffer" => unmap_buffer as *const c_void,
+        "glBindBufferRange" => bind_buffer_range as *const c_void,
+        "glGetBufferParameteri64v" => get_buffer_parameter as *const c_void,
+        "glBufferSubData" => buffer_sub_data as *const c_void,
+        "glGetBufferSubData" => get_buffer_sub_data as *const c_void,
+        _ => null(),
+    }
+}
+
+fn setup() -> MutexGuard<'static, ()> {
+    let guard = common::setup(stubs);
+    TRANSFERS.store(0, Ordering::SeqCst);
+    MAPS.store(0, Ordering::SeqCst);
+    UNMAPS.store(0, Ordering::SeqCst);
+    unsafe { STORAGE = [0; 4] };
+    guard
+}
+
+#[test]
+fn sub_data_within_bounds() {
+    let _guard = setup();
+    sgl::buffer_sub_data(BufferKind::Array, 0, &[0u32; 4]);
+    sgl::buffer_sub_data(BufferKind::Array, 12, &[0u8; 4]);
+    sgl::buffer_sub_data::<u8>(BufferKind::Array, 16, &[]);
+    sgl::get_buffer_sub_data(BufferKind::Array, 8, &mut [0u16; 4]);
+    assert_eq!(TRANSFERS.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+#[should_panic(expected = "range 8..24 is out of bounds of the 16 byte Array buffer")]
+fn sub_data_past_the_end_panics() {
+    let _guard = setup();
+    sgl::buffer_sub_data(BufferKind::Array, 8, &[0u32; 4]);
+}
+
+#[test]
+#[should_panic(expected = "is out of bounds of the 16 byte Uniform buffer")]
+fn get_sub_data_past_the_end_panics() {
+    let _guard = setup();
+    sgl::get_buffer_sub_data(BufferKind::Uniform, 1, &mut [0u8; 16]);
+}
+
+#[test]
+#[should_panic(expected = "is out of bounds")]
+fn sub_data_offset_overflow_panics() {
+    let _guard = setup();
+    sgl::buffer_sub_data(BufferKind::Array, usize::MAX, &[0u8; 2]);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "offset 64 of Uniform buffer range isn't aligned to 256 bytes")]
+fn misaligned_range_panics_in_debug_builds() {
+    let _guard = setup();
+    sgl::bind_buffer_range(IndexedBufferKind::Uniform, 0, Buffer::NONE, 256, 64);
+    sgl::bind_buffer_range(IndexedBufferKind::Uniform, 0, Buffer::NONE, 64, 64);
+}
+
+#[test]
+fn write_only_mapping() {
+    let _guard = setup();
+    let mut buffer = OwnedBuffer::new();
+    let mut mapping = map_buffer_range::<u32, MapWrite>(
+        BufferKind::Array,
+        &mut buffer,
+        4,
+        3,
+        MapAccess::InvalidateRange.into(),
+    )
+    .unwrap();
+    assert_eq!(mapping.len(), 3);
+    assert_eq!(
+        mapping.access(),
+        MapAccess::Write | MapAccess::InvalidateRange
+    );
+    mapping.copy_from_slice(&[1, 2, 3]);
+    mapping.write(2, 4);
+    mapping.unmap().unwrap();
+    assert_eq!(unsafe { STORAGE }, [0, 1, 2, 4]);
+}
+
+#[test]
+fn read_write_mapping_of_a_plain_buffer() {
+    let _guard = setup();
+    unsafe { STORAGE = [1, 2, 3, 4] };
+    let buffer = sgl::gen_buffer();
+    let mut mapping = unsafe {
+        map_buffer_range_raw::<u32, MapReadWrite>(
+            BufferKind::Array,
+            buffer,
+            0,
+            4,
+            MapAccess::Read.into(),
+        )
+    }
+    .unwrap();
+    for value in mapping.iter_mut() {
+        *value *= 2;
+    }
+    assert_eq!(*mapping, [2, 4, 6, 8]);
+    drop(mapping);
+    assert_eq!(UNMAPS.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+#[should_panic(expected = "mappings can't be made with")]
+fn read_mapping_with_write_access_panics() {
+    let _guard = setup();
+    let mut buffer = OwnedBuffer::new();
+    let _ = map_buffer_range::<u32, MapRead>(
+        BufferKind::Array,
+        &mut buffer,
+        0,
+        4,
+        MapAccess::Write.into(),
+    );
+}
+
+#[test]
+fn misaligned_mapping_panics_before_mapping() {
+    let _guard = setup();
+    let mut buffer = OwnedBuffer::new();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let _ = map_buffer_range::<u32, MapRead>(
+            BufferKind::Array,
+            &mut buffer,
+            2,
+            1,
+            BitFlags::empty(),
+        );
+    }));
+    assert!(result.is_err());
+    assert_eq!(MAPS.load(Ordering::SeqCst), 0);
+}
@@ -0,0 +1,40 @@
+//! Loader and serialisation shared by the tests that run against stubbed GL functions.
+
+#![allow(dead_code)]
+
+use std::{
+    ffi::c_void,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Mutex, MutexGuard, Once,
+    },
+};
+
+static NEXT_NAME: AtomicU32 = AtomicU32::new(1);
+static SERIAL: Mutex<()> = Mutex::new(());
+static LOAD: Once = Once::new();
+
+extern "system" fn get_error() -> u32 {
+    0 // GL_NO_ERROR, so the stubs also pass with the `checked` feature
+}
+
+/// Stub for every `glGen*`, hands out names that are unique within the test binary
+pub extern "system" fn gen_names(n: i32, names: *mut u32) {
+    for i in 0..n as usize {
+        unsafe { *names.add(i) = NEXT_NAME.fetch_add(1, Ordering::SeqCst) };
+    }
+}
+
+/// Loads `stubs` the first time it's called, with `glGetError` always reporting no error.
+///
+/// Stubs record into globals, so the returned guard serialises the tests of a binary; reset
+/// those globals after taking it.
+pub fn setup(stubs: fn(&str) -> *const c_void) -> MutexGuard<'static, ()> {
+    LOAD.call_once(|| {
+        sgl::load_with(|name| match name {
+            "glGetError" => get_error as *const c_void,
+            _ => stubs(name),
+        });
+    });
+    SERIAL.lock().unwrap_or_else(|e| e.into_inner())
+}
@@ -0,0 +1,191 @@
+//! Delivers messages through the debug callback the way a driver would, against a stubbed GL
+//! loader that keeps whatever `glDebugMessageCallback` was given.
+
+mod common;
+
+use std::{
+    convert::TryFrom,
+    ffi::c_void,
+    os::raw::c_char,
+    ptr::null,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+};
+
+use sgl::{Capability, DebugCallbackInfo, MessageType, Severity, Source, Type, UnknownEnumValue};
+
+type DebugProc = extern "system" fn(u32, u32, u32, u32, i32, *const c_char, *mut c_void);
+
+static CALLBACK: AtomicUsize = AtomicUsize::new(0);
+static USER_PARAM: AtomicUsize = AtomicUsize::new(0);
+
+extern "system" fn debug_message_callback(callback: Option<DebugProc>, user_param: *mut c_void) {
+    CALLBACK.store(callback.map_or(0, |f| f as usize), Ordering::SeqCst);
+    USER_PARAM.store(user_param as usize, Ordering::SeqCst);
+}
+
+extern "system" fn get_pointer(pname: u32, params: *mut *mut c_void) {
+    let value = match pname {
+        0x8244 => CALLBACK.load(Ordering::SeqCst), // GL_DEBUG_CALLBACK_FUNCTION
+        0x8245 => USER_PARAM.load(Ordering::SeqCst), // GL_DEBUG_CALLBACK_USER_PARAM
+        _ => 0,
+    };
+    unsafe { *params = value as *mut c_void };
+}
+
+extern "system" fn enable(_: u32) {}
+
+extern "system" fn disable(_: u32) {}
+
+fn stubs(name: &str) -> *const c_void {
+    match name {
+        "glDebugMessageCallback" => debug_message_callback as *const c_void,
+        "glGetPointerv" => get_pointer as *const c_void,
+        "glEnable" => enable as *const c_void,
+        "glDisable" => disable as *const c_void,
+        _ => null(),
+    }
+}
+
+fn setup() -> MutexGuard<'static, ()> {
+    let guard = common::setup(stubs);
+    sgl::debug_message_callback(None);
+    guard
+}
+
+/// Calls the installed callback like the driver would, `length` as given to it
+fn deliver(message: &[u8], length: i32) {
+    let callback = CALLBACK.load(Ordering::SeqCst);
+    assert_ne!(callback, 0, "no debug callback installed");
+    let callback: DebugProc = unsafe { std::mem::transmute(callback) };
+    callback(
+        0x824A, // GL_DEBUG_SOURCE_APPLICATION
+        0x824C, // GL_DEBUG_TYPE_ERROR
+        7,
+        0x9999, // not a severity
+        length,
+        message.as_ptr() as *const c_char,
+        USER_PARAM.load(Ordering::SeqCst) as *mut c_void,
+    );
+}
+
+/// Installs a callback that records every message it's given
+fn record() -> Arc<Mutex<Vec<(DebugCallbackInfo, String)>>> {
+    let messages = Arc::new(Mutex::new(Vec::new()));
+    let recorded = messages.clone();
+    sgl::debug_message_callback(Some(Box::new(move |info, message: &str| {
+        recorded.lock().unwrap().push((info, message.to_string()));
+    })));
+    messages
+}
+
+#[test]
+fn closed_enums_reject_unknown_values() {
+    assert_eq!(Type::try_from(0x1406), Ok(Type::Float)); // GL_FLOAT
+    let err = Type::try_from(0x1234).unwrap_err();
+    assert_eq!(
+        err,
+        UnknownEnumValue {
+            name: "Type",
+            value: 0x1234
+        }
+    );
+    assert_eq!(err.to_string(), "0x1234 is not a valid Type");
+}
+
+#[test]
+fn open_enums_keep_unknown_values() {
+    assert_eq!(Source::from(0x8246), Source::Api); // GL_DEBUG_SOURCE_API
+    assert_eq!(Severity::from(0x9999), Severity::Unknown(0x9999));
+    assert_eq!(u32::from(Severity::Unknown(0x9999)), 0x9999);
+    assert_eq!(u32::from(MessageType::Marker), 0x8268); // GL_DEBUG_TYPE_MARKER
+}
+
+#[test]
+fn message_decoding() {
+    let _guard = setup();
+    let messages = record();
+
+    // the length may or may not count the terminator
+    deliver(b"with terminator\0", 16);
+    deliver(b"without terminator", 18);
+    // a negative length means the message is null-terminated
+    deliver(b"negative length\0garbage", -1);
+    // invalid UTF-8 is replaced instead of panicking
+    deliver(b"bad \xFF byte", 10);
+
+    let messages = messages.lock().unwrap();
+    let text: Vec<&str> = messages.iter().map(|(_, m)| m.as_str()).collect();
+    assert_eq!(
+        text,
+        vec![
+            "with terminator",
+            "without terminator",
+            "negative length",
+            "bad \u{FFFD} byte"
+        ]
+    );
+    assert_eq!(
+        messages[0].0,
+        DebugCallbackInfo {
+            source: Source::Application,
+            message_type: MessageType::Error,
+            severity: Severity::Unknown(0x9999),
+            id: 7,
+        }
+    );
+}
+
+#[test]
+fn callback_can_replace_itself() {
+    let _guard = setup();
+    let messages = Arc::new(Mutex::new(Vec::new()));
+    let first = Arc::new(());
+
+    let (recorded, alive) = (messages.clone(), first.clone());
+    sgl::debug_message_callback(Some(Box::new(move |_, message: &str| {
+        let _alive = &alive;
+        recorded.lock().unwrap().push(format!("first: {}", message));
+        let recorded = recorded.clone();
+        sgl::debug_message_callback(Some(Box::new(move |_, message: &str| {
+            recorded
+                .lock()
+                .unwrap()
+                .push(format!("second: {}", message));
+        })));
+    })));
+
+    deliver(b"a", 1);
+    // the first callback is dropped once it returned
+    assert_eq!(Arc::strong_count(&first), 1);
+    deliver(b"b", 1);
+    assert_eq!(*messages.lock().unwrap(), vec!["first: a", "second: b"]);
+}
+
+#[test]
+#[should_panic(expected = "a non-Send debug callback is installed")]
+fn disabling_synchronous_output_under_local_callback_panics() {
+    let _guard = setup();
+    sgl::debug_message_callback_local(Box::new(|_, _| {}));
+    sgl::disable(Capability::DebugOutputSynchronous);
+}
+
+#[test]
+fn local_callback_is_per_context() {
+    let _guard = setup();
+    sgl::debug_message_callback_local(Box::new(|_, _| {}));
+
+    // another context without a callback
+    let (callback, user_param) = (
+        CALLBACK.swap(0, Ordering::SeqCst),
+        USER_PARAM.swap(0, Ordering::SeqCst),
+    );
+    sgl::disable(Capability::DebugOutputSynchronous);
+    CALLBACK.store(callback, Ordering::SeqCst);
+    USER_PARAM.store(user_param, Ordering::SeqCst);
+
+    sgl::debug_message_callback(None);
+    sgl::disable(Capability::DebugOutputSynchronous);
+}
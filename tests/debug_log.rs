@@ -0,0 +1,66 @@
+#![cfg(any(feature = "log", feature = "tracing"))]
+
+use std::time::{Duration, Instant};
+
+use sgl::{DebugCallbackInfo, MessageType, RateLimiter, Severity, Source};
+
+const WINDOW: Duration = Duration::from_secs(1);
+const TICK: Duration = Duration::from_millis(1);
+
+fn info(id: u32) -> DebugCallbackInfo {
+    DebugCallbackInfo {
+        source: Source::Api,
+        message_type: MessageType::Performance,
+        severity: Severity::Medium,
+        id,
+    }
+}
+
+#[test]
+fn repeats_past_the_burst_are_counted() {
+    let start = Instant::now();
+    let mut limiter = RateLimiter::new(2, WINDOW);
+    assert_eq!(limiter.check_at(&info(1), start), Some(0));
+    assert_eq!(limiter.check_at(&info(1), start), Some(0));
+    assert_eq!(limiter.check_at(&info(1), start + TICK), None);
+    assert_eq!(limiter.check_at(&info(1), start + WINDOW - TICK), None);
+    // other messages have their own window
+    assert_eq!(limiter.check_at(&info(2), start + TICK), Some(0));
+
+    assert_eq!(limiter.check_at(&info(1), start + WINDOW), Some(2));
+    assert_eq!(limiter.check_at(&info(1), start + WINDOW), Some(0));
+    assert!(limiter.take_expired().is_empty());
+}
+
+#[test]
+fn expired_windows_are_evicted() {
+    let start = Instant::now();
+    let mut limiter = RateLimiter::new(1, WINDOW);
+    for id in 0..10 {
+        limiter.check_at(&info(id), start);
+    }
+    limiter.check_at(&info(0), start + TICK); // suppressed
+    assert_eq!(limiter.tracked(), 10);
+
+    // kept for another window until the suppressed count has been reported
+    limiter.check_at(&info(1), start + WINDOW);
+    assert_eq!(limiter.tracked(), 2);
+    assert!(limiter.take_expired().is_empty());
+
+    limiter.check_at(&info(1), start + WINDOW * 2);
+    assert_eq!(limiter.tracked(), 1);
+}
+
+#[test]
+fn unreported_counts_are_handed_out_on_eviction() {
+    let start = Instant::now();
+    let mut limiter = RateLimiter::new(1, WINDOW);
+    limiter.check_at(&info(0), start);
+    limiter.check_at(&info(0), start + TICK);
+    limiter.check_at(&info(0), start + TICK * 2);
+
+    limiter.check_at(&info(1), start + WINDOW * 2);
+    assert_eq!(limiter.take_expired(), [(info(0), 2)]);
+    assert!(limiter.take_expired().is_empty());
+    assert_eq!(limiter.tracked(), 1);
+}
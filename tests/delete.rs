@@ -0,0 +1,132 @@
+//! Runs the deletion paths against a stubbed GL loader that records what
+//! actually reaches `glDeleteBuffers`/`glDeleteVertexArrays`.
+
+mod common;
+
+use std::{
+    ffi::c_void,
+    ptr::null,
+    sync::{Mutex, MutexGuard},
+};
+
+static DELETED_BUFFERS: Mutex<Vec<Vec<u32>>> = Mutex::new(Vec::new());
+static DELETED_ARRAYS: Mutex<Vec<Vec<u32>>> = Mutex::new(Vec::new());
+
+unsafe fn record(log: &Mutex<Vec<Vec<u32>>>, n: i32, names: *const u32) {
+    let names = std::slice::from_raw_parts(names, n as usize).to_vec();
+    log.lock().unwrap().push(names);
+}
+
+extern "system" fn delete_buffers(n: i32, names: *const u32) {
+    unsafe { record(&DELETED_BUFFERS, n, names) }
+}
+
+extern "system" fn delete_vertex_arrays(n: i32, names: *const u32) {
+    unsafe { record(&DELETED_ARRAYS, n, names) }
+}
+
+fn stubs(name: &str) -> *const c_void {
+    match name {
+        "glGenBuffers" | "glGenVertexArrays" => common::gen_names as *const c_void,
+        "glDeleteBuffers" => delete_buffers as *const c_void,
+        "glDeleteVertexArrays" => delete_vertex_arrays as *const c_void,
+        _ => null(),
+    }
+}
+
+fn setup() -> MutexGuard<'static, ()> {
+    let guard = common::setup(stubs);
+    DELETED_BUFFERS.lock().unwrap().clear();
+    DELETED_ARRAYS.lock().unwrap().clear();
+    guard
+}
+
+fn deleted_buffers() -> Vec<Vec<u32>> {
+    DELETED_BUFFERS.lock().unwrap().clone()
+}
+
+fn deleted_arrays() -> Vec<Vec<u32>> {
+    DELETED_ARRAYS.lock().unwrap().clone()
+}
+
+#[test]
+fn delete_single_buffer() {
+    let _guard = setup();
+    let buffer = sgl::gen_buffer();
+    sgl::delete_buffer(buffer);
+    assert_eq!(deleted_buffers(), vec![vec![*buffer]]);
+}
+
+#[test]
+fn delete_buffer_batch_includes_last() {
+    let _guard = setup();
+    let buffers = sgl::gen_buffers::<3>();
+    sgl::delete_buffers(&buffers);
+    assert_eq!(
+        deleted_buffers(),
+        vec![buffers.iter().map(|b| **b).collect::<Vec<_>>()]
+    );
+}
+
+#[test]
+fn delete_empty_buffer_slice_is_noop() {
+    let _guard = setup();
+    sgl::delete_buffers(&[]);
+    assert!(deleted_buffers().is_empty());
+}
+
+#[test]
+fn delete_buffers_skips_none() {
+    let _guard = setup();
+    let [a, b] = sgl::gen_buffers::<2>();
+    sgl::delete_buffers(&[sgl::Buffer::NONE, a, sgl::Buffer::NONE, b]);
+    assert_eq!(deleted_buffers(), vec![vec![*a, *b]]);
+
+    sgl::delete_buffer(sgl::Buffer::NONE);
+    assert_eq!(deleted_buffers().len(), 1);
+}
+
+#[test]
+fn owned_buffer_deletes_on_drop() {
+    let _guard = setup();
+    let owned = sgl::OwnedBuffer::new();
+    let name = **owned;
+    drop(owned);
+    assert_eq!(deleted_buffers(), vec![vec![name]]);
+
+    let raw = sgl::OwnedBuffer::new().into_raw();
+    assert_eq!(deleted_buffers().len(), 1);
+    sgl::delete_buffer(raw);
+    assert_eq!(deleted_buffers()[1], vec![*raw]);
+}
+
+#[test]
+fn delete_single_vertex_array() {
+    let _guard = setup();
+    let array = sgl::gen_vertex_array();
+    sgl::delete_vertex_array(array);
+    assert_eq!(deleted_arrays(), vec![vec![*array]]);
+}
+
+#[test]
+fn delete_vertex_array_batch_includes_last() {
+    let _guard = setup();
+    let arrays = sgl::gen_vertex_arrays::<4>();
+    sgl::delete_vertex_arrays(&arrays);
+    assert_eq!(
+        deleted_arrays(),
+        vec![arrays.iter().map(|a| **a).collect::<Vec<_>>()]
+    );
+}
+
+#[test]
+fn delete_vertex_arrays_skips_none_and_empty() {
+    let _guard = setup();
+    sgl::delete_vertex_arrays(&[]);
+    sgl::delete_vertex_arrays(&[sgl::VertexArray::NONE]);
+    assert!(deleted_arrays().is_empty());
+
+    let array = sgl::gen_vertex_array();
+    sgl::delete_vertex_arrays(&[array, sgl::VertexArray::NONE]);
+    assert_eq!(deleted_arrays(), vec![vec![*array]]);
+}
@@ -0,0 +1,207 @@
+//! Info log samples recorded from each vendor's GLSL compiler.
+
+use sgl::{parse_info_log, DiagnosticSeverity, ShaderDiagnostic, ShaderError, ShaderKind};
+
+const SOURCE: &str = "#version 330 core
+out vec4 color;
+
+void main() {
+    color = vec4(foo, 1.0);
+}
+";
+
+fn diag(
+    line: Option<u32>,
+    column: Option<u32>,
+    severity: DiagnosticSeverity,
+    message: &str,
+) -> ShaderDiagnostic {
+    ShaderDiagnostic {
+        line,
+        column,
+        severity,
+        message: message.to_string(),
+    }
+}
+
+#[test]
+fn mesa() {
+    let log = "0:5(18): error: `foo' undeclared\n\
+               0:5(13): error: no matching function for call to `vec4(error, float)'\n\
+               0:2(10): warning: `color' used uninitialized\n\0";
+
+    assert_eq!(
+        parse_info_log(log),
+        vec![
+            diag(Some(5), Some(18), DiagnosticSeverity::Error, "`foo' undeclared"),
+            diag(
+                Some(5),
+                Some(13),
+                DiagnosticSeverity::Error,
+                "no matching function for call to `vec4(error, float)'"
+            ),
+            diag(
+                Some(2),
+                Some(10),
+                DiagnosticSeverity::Warning,
+                "`color' used uninitialized"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn nvidia() {
+    let log = "0(5) : error C1008: undefined variable \"foo\"\n\
+               0(5) : error C1102: incompatible type for parameter #1 (\"x\")\n\
+               0(1) : warning C7555: 'varying' is deprecated, use 'in/out' instead\n";
+
+    assert_eq!(
+        parse_info_log(log),
+        vec![
+            diag(
+                Some(5),
+                None,
+                DiagnosticSeverity::Error,
+                "C1008: undefined variable \"foo\""
+            ),
+            diag(
+                Some(5),
+                None,
+                DiagnosticSeverity::Error,
+                "C1102: incompatible type for parameter #1 (\"x\")"
+            ),
+            diag(
+                Some(1),
+                None,
+                DiagnosticSeverity::Warning,
+                "C7555: 'varying' is deprecated, use 'in/out' instead"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn amd() {
+    let log = "ERROR: 0:5: 'foo' : undeclared identifier \n\
+               ERROR: 0:5: 'constructor' : not enough data provided for construction \n\
+               ERROR: 2 compilation errors.  No code generated.\n\n";
+
+    assert_eq!(
+        parse_info_log(log),
+        vec![
+            diag(
+                Some(5),
+                None,
+                DiagnosticSeverity::Error,
+                "'foo' : undeclared identifier"
+            ),
+            diag(
+                Some(5),
+                None,
+                DiagnosticSeverity::Error,
+                "'constructor' : not enough data provided for construction"
+            ),
+            diag(
+                None,
+                None,
+                DiagnosticSeverity::Error,
+                "2 compilation errors.  No code generated."
+            ),
+        ]
+    );
+}
+
+#[test]
+fn intel() {
+    let log = "ERROR: 0:5: error(#143) Undeclared identifier: foo\n\
+               WARNING: 0:2: warning(#402) Implicit truncation of vector from size: 4 to size: 3\n\
+               ERROR: error(#273) 1 compilation errors.  No code generated\n";
+
+    assert_eq!(
+        parse_info_log(log),
+        vec![
+            diag(
+                Some(5),
+                None,
+                DiagnosticSeverity::Error,
+                "error(#143) Undeclared identifier: foo"
+            ),
+            diag(
+                Some(2),
+                None,
+                DiagnosticSeverity::Warning,
+                "warning(#402) Implicit truncation of vector from size: 4 to size: 3"
+            ),
+            diag(
+                None,
+                None,
+                DiagnosticSeverity::Error,
+                "error(#273) 1 compilation errors.  No code generated"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn continuation_lines_are_joined() {
+    let log = "0(5) : error C1008: undefined variable \"foo\"\n    (declared nowhere)\n";
+    let diagnostics = parse_info_log(log);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].message,
+        "C1008: undefined variable \"foo\"\n(declared nowhere)"
+    );
+}
+
+#[test]
+fn empty_log() {
+    assert!(parse_info_log("").is_empty());
+    assert!(parse_info_log("\0").is_empty());
+}
+
+#[test]
+fn render_with_column() {
+    let d = diag(Some(5), Some(18), DiagnosticSeverity::Error, "`foo' undeclared");
+    assert_eq!(
+        d.render(SOURCE),
+        "error: `foo' undeclared\n \
+         --> 5:18\n  \
+         |\n\
+         5 |     color = vec4(foo, 1.0);\n  \
+         |                  ^\n"
+    );
+}
+
+#[test]
+fn render_without_column() {
+    let d = diag(Some(5), None, DiagnosticSeverity::Warning, "C0000: oops");
+    assert_eq!(
+        d.render(SOURCE),
+        "warning: C0000: oops\n \
+         --> 5\n  \
+         |\n\
+         5 |     color = vec4(foo, 1.0);\n  \
+         |     ^^^^^^^^^^^^^^^^^^^^^^^\n"
+    );
+}
+
+#[test]
+fn render_out_of_range() {
+    let d = diag(Some(99), None, DiagnosticSeverity::Error, "eof");
+    assert_eq!(d.render(SOURCE), "error: eof\n --> 99\n");
+
+    let d = diag(None, None, DiagnosticSeverity::Error, "no location");
+    assert_eq!(d.render(SOURCE), "error: no location\n");
+}
+
+#[test]
+fn shader_error_diagnostics() {
+    let error = ShaderError {
+        kind: ShaderKind::Fragment,
+        source: SOURCE.to_string(),
+        log: "0:5(18): error: `foo' undeclared\n".to_string(),
+    };
+    assert_eq!(error.diagnostics().len(), 1);
+    assert!(error.render().contains("5 |     color = vec4(foo, 1.0);"));
+}
@@ -0,0 +1,66 @@
+//! Checks the stride validation of multi indirect draws against a stubbed GL loader.
+
+mod common;
+
+use std::{
+    ffi::c_void,
+    ptr::null,
+    sync::{Mutex, MutexGuard},
+};
+
+use sgl::{DrawMode, IndexType};
+
+/// The `stride` of every multi indirect draw that reached GL
+static STRIDES: Mutex<Vec<i32>> = Mutex::new(Vec::new());
+
+extern "system" fn multi_draw_arrays_indirect(_: u32, _: *const c_void, _: i32, stride: i32) {
+    STRIDES.lock().unwrap().push(stride);
+}
+
+extern "system" fn multi_draw_elements_indirect(
+    _: u32,
+    _: u32,
+    _: *const c_void,
+    _: i32,
+    stride: i32,
+) {
+    STRIDES.lock().unwrap().push(stride);
+}
+
+fn stubs(name: &str) -> *const c_void {
+    match name {
+        "glMultiDrawArraysIndirect" => multi_draw_arrays_indirect as *const c_void,
+        "glMultiDrawElementsIndirect" => multi_draw_elements_indirect as *const c_void,
+        _ => null(),
+    }
+}
+
+fn setup() -> MutexGuard<'static, ()> {
+    let guard = common::setup(stubs);
+    STRIDES.lock().unwrap().clear();
+    guard
+}
+
+#[test]
+fn packed_and_padded_strides_are_passed() {
+    let _guard = setup();
+    sgl::multi_draw_arrays_indirect(DrawMode::Triangles, 0, 2, 0);
+    sgl::multi_draw_arrays_indirect(DrawMode::Triangles, 0, 2, 16);
+    sgl::multi_draw_elements_indirect(DrawMode::Triangles, IndexType::UnsignedInt, 0, 2, 20);
+    sgl::multi_draw_elements_indirect(DrawMode::Triangles, IndexType::UnsignedInt, 0, 2, 32);
+    assert_eq!(*STRIDES.lock().unwrap(), [0, 16, 20, 32]);
+}
+
+#[test]
+#[should_panic(expected = "indirect draw stride can't be negative, got -16")]
+fn negative_stride_panics() {
+    let _guard = setup();
+    sgl::multi_draw_arrays_indirect(DrawMode::Triangles, 0, 2, -16);
+}
+
+#[test]
+#[should_panic(expected = "indirect draw stride must be 0 or at least 20 bytes, got 16")]
+fn overlapping_stride_panics() {
+    let _guard = setup();
+    sgl::multi_draw_elements_indirect(DrawMode::Triangles, IndexType::UnsignedInt, 0, 2, 16);
+}
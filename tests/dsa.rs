@@ -0,0 +1,45 @@
+//! Checks how missing direct state access is reported, against a stubbed GL 4.3 loader without
+//! `GL_ARB_direct_state_access`.
+
+mod common;
+
+use std::{ffi::c_void, ptr::null, sync::MutexGuard};
+
+extern "system" fn get_integer(pname: u32, data: *mut i32) {
+    let value = match pname {
+        0x821B => 4, // GL_MAJOR_VERSION
+        0x821C => 3, // GL_MINOR_VERSION
+        0x821D => 1, // GL_NUM_EXTENSIONS
+        _ => 0,
+    };
+    unsafe { *data = value };
+}
+
+extern "system" fn get_string_i(_: u32, _: u32) -> *const u8 {
+    b"GL_ARB_buffer_storage\0".as_ptr()
+}
+
+fn stubs(name: &str) -> *const c_void {
+    match name {
+        "glGetIntegerv" => get_integer as *const c_void,
+        "glGetStringi" => get_string_i as *const c_void,
+        _ => null(),
+    }
+}
+
+fn setup() -> MutexGuard<'static, ()> {
+    common::setup(stubs)
+}
+
+#[test]
+fn unavailable_before_4_5() {
+    let _guard = setup();
+    assert!(!sgl::has_direct_state_access());
+}
+
+#[test]
+#[should_panic(expected = "glCreateBuffers isn't available")]
+fn create_panics_when_not_loaded() {
+    let _guard = setup();
+    sgl::create_buffer();
+}
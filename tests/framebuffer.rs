@@ -0,0 +1,158 @@
+//! Drives `FramebufferBuilder` against a stubbed GL loader to check what gets attached, which
+//! draw buffers are set and that incomplete framebuffers are cleaned up.
+
+mod common;
+
+use std::{
+    ffi::c_void,
+    ptr::null,
+    sync::{
+        atomic::{AtomicU32, AtomicUsize, Ordering},
+        Mutex, MutexGuard,
+    },
+};
+
+use sgl::{
+    Attachment, AttachmentDesc, FramebufferBuilder, FramebufferStatus, InternalFormat, Renderbuffer,
+};
+
+const COLOR_ATTACHMENT0: u32 = 0x8CE0;
+
+static STATUS: AtomicU32 = AtomicU32::new(0x8CD5); // GL_FRAMEBUFFER_COMPLETE
+static DELETED_FRAMEBUFFERS: AtomicUsize = AtomicUsize::new(0);
+static DELETED_RENDERBUFFERS: AtomicUsize = AtomicUsize::new(0);
+static ATTACHED: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+static DRAW_BUFFERS: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+static READ_BUFFER: AtomicU32 = AtomicU32::new(u32::MAX);
+
+extern "system" fn delete_framebuffers(n: i32, _: *const u32) {
+    DELETED_FRAMEBUFFERS.fetch_add(n as usize, Ordering::SeqCst);
+}
+
+extern "system" fn delete_renderbuffers(n: i32, _: *const u32) {
+    DELETED_RENDERBUFFERS.fetch_add(n as usize, Ordering::SeqCst);
+}
+
+extern "system" fn bind(_: u32, _: u32) {}
+
+extern "system" fn renderbuffer_storage_multisample(_: u32, _: i32, _: u32, _: i32, _: i32) {}
+
+extern "system" fn framebuffer_texture(_: u32, attachment: u32, _: u32, _: i32) {
+    ATTACHED.lock().unwrap().push(attachment);
+}
+
+extern "system" fn framebuffer_renderbuffer(_: u32, attachment: u32, _: u32, _: u32) {
+    ATTACHED.lock().unwrap().push(attachment);
+}
+
+extern "system" fn draw_buffers(n: i32, buffers: *const u32) {
+    let buffers = unsafe { std::slice::from_raw_parts(buffers, n as usize) };
+    *DRAW_BUFFERS.lock().unwrap() = buffers.to_vec();
+}
+
+extern "system" fn read_buffer(buffer: u32) {
+    READ_BUFFER.store(buffer, Ordering::SeqCst);
+}
+
+extern "system" fn check_framebuffer_status(_: u32) -> u32 {
+    STATUS.load(Ordering::SeqCst)
+}
+
+fn stubs(name: &str) -> *const c_void {
+    match name {
+        "glGenFramebuffers" | "glGenRenderbuffers" | "glGenTextures" => {
+            common::gen_names as *const c_void
+        }
+        "glDeleteFramebuffers" => delete_framebuffers as *const c_void,
+        "glDeleteRenderbuffers" => delete_renderbuffers as *const c_void,
+        "glBindFramebuffer" | "glBindRenderbuffer" => bind as *const c_void,
+        "glRenderbufferStorageMultisample" => renderbuffer_storage_multisample as *const c_void,
+        "glFramebufferTexture" => framebuffer_texture as *const c_void,
+        "glFramebufferRenderbuffer" => framebuffer_renderbuffer as *const c_void,
+        "glDrawBuffers" => draw_buffers as *const c_void,
+        "glReadBuffer" => read_buffer as *const c_void,
+        "glCheckFramebufferStatus" => check_framebuffer_status as *const c_void,
+        _ => null(),
+    }
+}
+
+fn setup(status: u32) -> MutexGuard<'static, ()> {
+    let guard = common::setup(stubs);
+    STATUS.store(status, Ordering::SeqCst);
+    DELETED_FRAMEBUFFERS.store(0, Ordering::SeqCst);
+    DELETED_RENDERBUFFERS.store(0, Ordering::SeqCst);
+    ATTACHED.lock().unwrap().clear();
+    DRAW_BUFFERS.lock().unwrap().clear();
+    READ_BUFFER.store(u32::MAX, Ordering::SeqCst);
+    guard
+}
+
+#[test]
+fn complete_framebuffer() {
+    let _guard = setup(0x8CD5);
+    let texture = sgl::gen_texture();
+    let fbo = FramebufferBuilder::new(64, 64)
+        .color(2, AttachmentDesc::Texture { texture, level: 0 })
+        .color(
+            0,
+            AttachmentDesc::NewRenderbuffer {
+                format: InternalFormat::RGBA8,
+                samples: 0,
+            },
+        )
+        .depth(AttachmentDesc::NewRenderbuffer {
+            format: InternalFormat::DepthComponent24,
+            samples: 0,
+        })
+        .build()
+        .unwrap();
+
+    assert_eq!(ATTACHED.lock().unwrap().len(), 3);
+    // output locations map to the attachment of the same index, location 1 is discarded
+    assert_eq!(
+        *DRAW_BUFFERS.lock().unwrap(),
+        vec![COLOR_ATTACHMENT0, 0, COLOR_ATTACHMENT0 + 2]
+    );
+    assert_eq!(READ_BUFFER.load(Ordering::SeqCst), COLOR_ATTACHMENT0);
+    assert!(fbo.renderbuffer(Attachment::Depth).is_some());
+    assert_eq!(fbo.renderbuffer(Attachment::Color(2)), None);
+
+    drop(fbo);
+    assert_eq!(DELETED_FRAMEBUFFERS.load(Ordering::SeqCst), 1);
+    assert_eq!(DELETED_RENDERBUFFERS.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn depth_only_has_no_draw_buffers() {
+    let _guard = setup(0x8CD5);
+    FramebufferBuilder::new(64, 64)
+        .depth(AttachmentDesc::Renderbuffer(Renderbuffer::NONE))
+        .build()
+        .unwrap();
+    assert_eq!(*DRAW_BUFFERS.lock().unwrap(), vec![0]);
+    assert_eq!(READ_BUFFER.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn incomplete_framebuffer_is_deleted() {
+    let _guard = setup(0x8CD7); // GL_FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT
+    let result = FramebufferBuilder::new(64, 64)
+        .depth_stencil(AttachmentDesc::NewRenderbuffer {
+            format: InternalFormat::Depth24Stencil8,
+            samples: 4,
+        })
+        .build();
+
+    let status = result.unwrap_err();
+    assert_eq!(status, FramebufferStatus::IncompleteMissingAttachment);
+    assert_eq!(status.to_string(), "no images are attached");
+    assert_eq!(DELETED_FRAMEBUFFERS.load(Ordering::SeqCst), 1);
+    assert_eq!(DELETED_RENDERBUFFERS.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn unknown_status_is_kept() {
+    let _guard = setup(0x1234);
+    let status = FramebufferBuilder::new(1, 1).build().unwrap_err();
+    assert_eq!(status, FramebufferStatus::Unknown(0x1234));
+}
@@ -0,0 +1,73 @@
+//! Packed vertex format helpers, checked against values worked out from the GL spec.
+
+use sgl::{
+    pack_10f_11f_11f_rev, pack_snorm_2_10_10_10_rev, pack_unorm_2_10_10_10_rev,
+    unpack_10f_11f_11f_rev, unpack_snorm_2_10_10_10_rev, unpack_unorm_2_10_10_10_rev, Type,
+};
+
+#[test]
+fn sizes() {
+    assert_eq!(Type::HalfFloat.size(), 2);
+    assert_eq!(Type::Fixed.size(), 4);
+    assert_eq!(Type::Int_2_10_10_10_Rev.size(), 4);
+    assert_eq!(Type::UnsignedInt_10F_11F_11F_Rev.size(), 4);
+    assert_eq!(Type::UnsignedInt_10F_11F_11F_Rev.component_counts(), &[3]);
+    assert!(Type::UnsignedShort.valid_for_ipointer());
+    assert!(!Type::Fixed.valid_for_ipointer());
+}
+
+#[test]
+fn snorm_2_10_10_10() {
+    assert_eq!(pack_snorm_2_10_10_10_rev([1.0, 0.0, 0.0, 0.0]), 0x1FF);
+    assert_eq!(pack_snorm_2_10_10_10_rev([-1.0, 0.0, 0.0, 0.0]), 0x201);
+    assert_eq!(
+        pack_snorm_2_10_10_10_rev([0.0, 0.0, 0.0, -1.0]),
+        0xC000_0000
+    );
+    assert_eq!(pack_snorm_2_10_10_10_rev([0.0, 2.0, 0.0, 0.0]), 0x1FF << 10);
+
+    let normal = [0.0, -0.6, 0.8, 1.0];
+    let unpacked = unpack_snorm_2_10_10_10_rev(pack_snorm_2_10_10_10_rev(normal));
+    for (a, b) in normal.iter().zip(&unpacked) {
+        assert!(
+            (a - b).abs() <= 1.0 / 511.0,
+            "{:?} != {:?}",
+            normal,
+            unpacked
+        );
+    }
+
+    // -512 is the second way of writing -1
+    assert_eq!(unpack_snorm_2_10_10_10_rev(0x200)[0], -1.0);
+}
+
+#[test]
+fn unorm_2_10_10_10() {
+    assert_eq!(pack_unorm_2_10_10_10_rev([1.0, 1.0, 1.0, 1.0]), u32::MAX);
+    assert_eq!(pack_unorm_2_10_10_10_rev([0.5, -1.0, 0.0, 0.0]), 512);
+    assert_eq!(unpack_unorm_2_10_10_10_rev(u32::MAX), [1.0; 4]);
+}
+
+#[test]
+fn float_10f_11f_11f() {
+    // 1.0 has a biased exponent of 15 and an empty mantissa
+    assert_eq!(pack_10f_11f_11f_rev([1.0, 0.0, 0.0]), 15 << 6);
+    assert_eq!(pack_10f_11f_11f_rev([0.0, 0.0, 1.0]), 15 << 5 << 22);
+    assert_eq!(pack_10f_11f_11f_rev([-3.0, 0.0, 0.0]), 0);
+
+    let color = [0.25, 1.5, 64.0];
+    assert_eq!(unpack_10f_11f_11f_rev(pack_10f_11f_11f_rev(color)), color);
+
+    // largest finite 11 bit float is 65024
+    let [r, g, b] = unpack_10f_11f_11f_rev(pack_10f_11f_11f_rev([1e9, f32::INFINITY, f32::NAN]));
+    assert_eq!(r, 65024.0);
+    assert_eq!(g, f32::INFINITY);
+    assert!(b.is_nan());
+
+    // smallest denormal of the 11 bit format
+    let tiny = 2f32.powi(-20);
+    assert_eq!(
+        unpack_10f_11f_11f_rev(pack_10f_11f_11f_rev([tiny, 0.0, 0.0]))[0],
+        tiny
+    );
+}
@@ -0,0 +1,75 @@
+//! Checks how compile and link failures pick up the info log, against a stubbed GL loader whose
+//! logs end in invalid UTF-8.
+
+mod common;
+
+use std::{ffi::c_void, ptr::null, sync::MutexGuard};
+
+use sgl::{Program, Shader, ShaderKind};
+
+/// What the driver reports, `\xFF` isn't valid UTF-8
+const LOG: &[u8] = b"0:1(1): error: syntax error \xFF!";
+
+extern "system" fn create() -> u32 {
+    1
+}
+
+extern "system" fn create_shader(_: u32) -> u32 {
+    1
+}
+
+extern "system" fn ignore(_: u32) {}
+
+extern "system" fn shader_source(_: u32, _: i32, _: *const *const i8, _: *const i32) {}
+
+extern "system" fn get_iv(_: u32, pname: u32, params: *mut i32) {
+    let value = match pname {
+        0x8B84 => LOG.len() as i32 + 1, // GL_INFO_LOG_LENGTH, with the NUL terminator
+        _ => 0,                         // GL_COMPILE_STATUS/GL_LINK_STATUS are false
+    };
+    unsafe { *params = value };
+}
+
+/// Writes as much of the log as fits in `buf_size` with the NUL terminator, like GL
+extern "system" fn get_info_log(_: u32, buf_size: i32, length: *mut i32, info_log: *mut i8) {
+    let written = LOG.len().min(buf_size as usize - 1);
+    unsafe {
+        std::ptr::copy_nonoverlapping(LOG.as_ptr(), info_log as *mut u8, written);
+        *info_log.add(written) = 0;
+        if !length.is_null() {
+            *length = written as i32;
+        }
+    }
+}
+
+fn stubs(name: &str) -> *const c_void {
+    match name {
+        "glCreateShader" => create_shader as *const c_void,
+        "glCreateProgram" => create as *const c_void,
+        "glShaderSource" => shader_source as *const c_void,
+        "glCompileShader" | "glLinkProgram" | "glDeleteShader" | "glDeleteProgram" => {
+            ignore as *const c_void
+        }
+        "glGetShaderiv" | "glGetProgramiv" => get_iv as *const c_void,
+        "glGetShaderInfoLog" | "glGetProgramInfoLog" => get_info_log as *const c_void,
+        _ => null(),
+    }
+}
+
+fn setup() -> MutexGuard<'static, ()> {
+    common::setup(stubs)
+}
+
+#[test]
+fn compile_error_keeps_the_whole_log() {
+    let _guard = setup();
+    let err = Shader::compile(ShaderKind::Fragment, "void main() {").unwrap_err();
+    assert_eq!(err.log, "0:1(1): error: syntax error \u{FFFD}!");
+}
+
+#[test]
+fn link_error_keeps_the_whole_log() {
+    let _guard = setup();
+    let err = Program::link(&[]).unwrap_err();
+    assert_eq!(err.log, "0:1(1): error: syntax error \u{FFFD}!");
+}
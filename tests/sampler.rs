@@ -0,0 +1,129 @@
+//! Drives `SamplerCache` against a stubbed GL loader to check samplers are shared and deleted.
+
+mod common;
+
+use std::{
+    ffi::c_void,
+    ptr::null,
+    sync::{
+        atomic::{AtomicI32, AtomicUsize, Ordering},
+        MutexGuard,
+    },
+};
+
+use sgl::{MinFilter, SamplerCache, SamplerDesc, WrapMode};
+
+static DELETED: AtomicUsize = AtomicUsize::new(0);
+static ANISOTROPY_SET: AtomicUsize = AtomicUsize::new(0);
+/// Anisotropic filtering is core from 4.6, no extensions are reported
+static MINOR_VERSION: AtomicI32 = AtomicI32::new(6);
+static VERSION_QUERIES: AtomicUsize = AtomicUsize::new(0);
+
+extern "system" fn get_integer(pname: u32, data: *mut i32) {
+    let value = match pname {
+        0x821B => {
+            VERSION_QUERIES.fetch_add(1, Ordering::SeqCst);
+            4 // GL_MAJOR_VERSION
+        }
+        0x821C => MINOR_VERSION.load(Ordering::SeqCst), // GL_MINOR_VERSION
+        _ => 0,
+    };
+    unsafe { *data = value };
+}
+
+extern "system" fn delete_samplers(n: i32, _: *const u32) {
+    DELETED.fetch_add(n as usize, Ordering::SeqCst);
+}
+
+extern "system" fn sampler_parameter_i(_: u32, _: u32, _: i32) {}
+
+extern "system" fn sampler_parameter_f(_: u32, pname: u32, _: f32) {
+    if pname == 0x84FE {
+        ANISOTROPY_SET.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+extern "system" fn sampler_parameter_fv(_: u32, _: u32, _: *const f32) {}
+
+fn stubs(name: &str) -> *const c_void {
+    match name {
+        "glGetIntegerv" => get_integer as *const c_void,
+        "glGenSamplers" => common::gen_names as *const c_void,
+        "glDeleteSamplers" => delete_samplers as *const c_void,
+        "glSamplerParameteri" => sampler_parameter_i as *const c_void,
+        "glSamplerParameterf" => sampler_parameter_f as *const c_void,
+        "glSamplerParameterfv" => sampler_parameter_fv as *const c_void,
+        _ => null(),
+    }
+}
+
+fn setup(minor_version: i32) -> MutexGuard<'static, ()> {
+    let guard = common::setup(stubs);
+    DELETED.store(0, Ordering::SeqCst);
+    ANISOTROPY_SET.store(0, Ordering::SeqCst);
+    MINOR_VERSION.store(minor_version, Ordering::SeqCst);
+    VERSION_QUERIES.store(0, Ordering::SeqCst);
+    guard
+}
+
+#[test]
+fn equal_descs_share_a_sampler() {
+    let _guard = setup(6);
+    let mut cache = SamplerCache::new();
+    let linear = SamplerDesc {
+        min_filter: MinFilter::Linear,
+        ..SamplerDesc::default()
+    };
+    let clamped = SamplerDesc {
+        wrap: [WrapMode::ClampToEdge; 3],
+        ..linear
+    };
+
+    let a = cache.get(&linear);
+    assert_eq!(cache.get(&linear), a);
+    assert_ne!(cache.get(&clamped), a);
+    assert_eq!(cache.len(), 2);
+
+    cache.clear();
+    assert_eq!(DELETED.load(Ordering::SeqCst), 2);
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn anisotropy_reset_when_supported() {
+    let _guard = setup(6);
+    let mut cache = SamplerCache::new();
+    cache.get(&SamplerDesc {
+        anisotropy: 8.0,
+        ..SamplerDesc::default()
+    });
+    // a default description puts it back to 1.0 instead of leaving whatever was there
+    cache.get(&SamplerDesc::default());
+    assert_eq!(ANISOTROPY_SET.load(Ordering::SeqCst), 2);
+    // support is only looked up once per cache
+    assert_eq!(VERSION_QUERIES.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn anisotropy_skipped_when_unsupported() {
+    let _guard = setup(5);
+    let mut cache = SamplerCache::new();
+    cache.get(&SamplerDesc::default());
+    assert_eq!(ANISOTROPY_SET.load(Ordering::SeqCst), 0);
+
+    cache.get(&SamplerDesc {
+        anisotropy: 8.0,
+        ..SamplerDesc::default()
+    });
+    assert_eq!(ANISOTROPY_SET.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+#[should_panic(expected = "lod range NaN..=1000 is empty")]
+fn nan_lod_range_panics() {
+    let _guard = setup(6);
+    SamplerCache::new().get(&SamplerDesc {
+        lod_range: (f32::NAN, 1000.0),
+        ..SamplerDesc::default()
+    });
+}
@@ -0,0 +1,196 @@
+//! Checks the typed `SamplerParameters` setters and getters of `TextureTarget` against a stubbed
+//! GL loader that records every parameter set and answers queries from a table.
+
+mod common;
+
+use std::{
+    ffi::c_void,
+    ptr::null,
+    slice,
+    sync::{Mutex, MutexGuard},
+};
+
+use sgl::{
+    CompareFunc, MagFilter, MinFilter, SamplerParameters, Swizzle, TextureProp, TextureTarget,
+    WrapMode,
+};
+
+const TEXTURE_WRAP_S: u32 = 0x2802;
+const TEXTURE_WRAP_T: u32 = 0x2803;
+const TEXTURE_WRAP_R: u32 = 0x8072;
+
+static INT_PARAMS: Mutex<Vec<(u32, Vec<i32>)>> = Mutex::new(Vec::new());
+static FLOAT_PARAMS: Mutex<Vec<(u32, Vec<f32>)>> = Mutex::new(Vec::new());
+/// What `glGetTexParameteriv` answers, unlisted parameters are 0
+static QUERIES: Mutex<Vec<(u32, i32)>> = Mutex::new(Vec::new());
+
+extern "system" fn tex_parameter_i(_: u32, pname: u32, param: i32) {
+    INT_PARAMS.lock().unwrap().push((pname, vec![param]));
+}
+
+extern "system" fn tex_parameter_f(_: u32, pname: u32, param: f32) {
+    FLOAT_PARAMS.lock().unwrap().push((pname, vec![param]));
+}
+
+fn components(pname: u32) -> usize {
+    match pname {
+        0x1004 | 0x8E46 => 4, // GL_TEXTURE_BORDER_COLOR, GL_TEXTURE_SWIZZLE_RGBA
+        _ => 1,
+    }
+}
+
+extern "system" fn tex_parameter_iv(_: u32, pname: u32, params: *const i32) {
+    let params = unsafe { slice::from_raw_parts(params, components(pname)) };
+    INT_PARAMS.lock().unwrap().push((pname, params.to_vec()));
+}
+
+extern "system" fn tex_parameter_fv(_: u32, pname: u32, params: *const f32) {
+    let params = unsafe { slice::from_raw_parts(params, components(pname)) };
+    FLOAT_PARAMS.lock().unwrap().push((pname, params.to_vec()));
+}
+
+extern "system" fn get_tex_parameter_iv(_: u32, pname: u32, params: *mut i32) {
+    let value = QUERIES
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(p, _)| *p == pname)
+        .map_or(0, |(_, value)| *value);
+    unsafe { *params = value };
+}
+
+fn stubs(name: &str) -> *const c_void {
+    match name {
+        "glTexParameteri" => tex_parameter_i as *const c_void,
+        "glTexParameterf" => tex_parameter_f as *const c_void,
+        "glTexParameteriv" => tex_parameter_iv as *const c_void,
+        "glTexParameterfv" => tex_parameter_fv as *const c_void,
+        "glGetTexParameteriv" => get_tex_parameter_iv as *const c_void,
+        _ => null(),
+    }
+}
+
+fn setup() -> MutexGuard<'static, ()> {
+    let guard = common::setup(stubs);
+    INT_PARAMS.lock().unwrap().clear();
+    FLOAT_PARAMS.lock().unwrap().clear();
+    QUERIES.lock().unwrap().clear();
+    guard
+}
+
+fn int_params() -> Vec<(u32, Vec<i32>)> {
+    INT_PARAMS.lock().unwrap().drain(..).collect()
+}
+
+fn float_params() -> Vec<(u32, Vec<f32>)> {
+    FLOAT_PARAMS.lock().unwrap().drain(..).collect()
+}
+
+#[test]
+fn typed_setters_send_their_pname() {
+    let _guard = setup();
+    let target = TextureTarget::Texture2D;
+    target.set_wrap(WrapMode::ClampToEdge);
+    target.set_min_filter(MinFilter::LinearMipmapLinear);
+    target.set_mag_filter(MagFilter::Nearest);
+    assert_eq!(
+        int_params(),
+        [
+            (TEXTURE_WRAP_S, vec![0x812F]), // GL_CLAMP_TO_EDGE
+            (TEXTURE_WRAP_T, vec![0x812F]),
+            (TEXTURE_WRAP_R, vec![0x812F]),
+            (0x2801, vec![0x2703]), // GL_TEXTURE_MIN_FILTER, GL_LINEAR_MIPMAP_LINEAR
+            (0x2800, vec![0x2600]), // GL_TEXTURE_MAG_FILTER, GL_NEAREST
+        ]
+    );
+
+    target.set_lod_range(1.0, 4.0);
+    target.set_lod_bias(-0.5);
+    target.set_anisotropy(8.0);
+    target.set_border_color([0.0, 0.25, 0.5, 1.0]);
+    assert_eq!(
+        float_params(),
+        [
+            (0x813A, vec![1.0]),                 // GL_TEXTURE_MIN_LOD
+            (0x813B, vec![4.0]),                 // GL_TEXTURE_MAX_LOD
+            (0x8501, vec![-0.5]),                // GL_TEXTURE_LOD_BIAS
+            (0x84FE, vec![8.0]),                 // GL_TEXTURE_MAX_ANISOTROPY
+            (0x1004, vec![0.0, 0.25, 0.5, 1.0]), // GL_TEXTURE_BORDER_COLOR
+        ]
+    );
+}
+
+#[test]
+fn compare_mode_follows_the_func() {
+    let _guard = setup();
+    let target = TextureTarget::Texture2D;
+    target.set_compare(Some(CompareFunc::LessEqual));
+    assert_eq!(
+        int_params(),
+        [
+            (0x884C, vec![0x884E]), // GL_TEXTURE_COMPARE_MODE, GL_COMPARE_REF_TO_TEXTURE
+            (0x884D, vec![0x0203]), // GL_TEXTURE_COMPARE_FUNC, GL_LEQUAL
+        ]
+    );
+
+    target.set_compare(None);
+    assert_eq!(int_params(), [(0x884C, vec![0])]);
+}
+
+#[test]
+fn swizzle_is_set_at_once() {
+    let _guard = setup();
+    TextureTarget::Texture2D.set_swizzle([
+        Swizzle::Blue,
+        Swizzle::Green,
+        Swizzle::Red,
+        Swizzle::One,
+    ]);
+    // GL_TEXTURE_SWIZZLE_RGBA with GL_BLUE, GL_GREEN, GL_RED and GL_ONE
+    assert_eq!(int_params(), [(0x8E46, vec![0x1905, 0x1904, 0x1903, 1])]);
+}
+
+#[test]
+fn wrap_modes_are_decoded() {
+    let _guard = setup();
+    QUERIES.lock().unwrap().extend([
+        (TEXTURE_WRAP_S, 0x2901), // GL_REPEAT
+        (TEXTURE_WRAP_T, 0x8370), // GL_MIRRORED_REPEAT
+        (TEXTURE_WRAP_R, 0x812D), // GL_CLAMP_TO_BORDER
+    ]);
+    assert_eq!(
+        TextureTarget::Texture2D.get_wrap(),
+        Ok([
+            WrapMode::Repeat,
+            WrapMode::MirroredRepeat,
+            WrapMode::ClampToBorder
+        ])
+    );
+
+    QUERIES.lock().unwrap()[0].1 = 0x1234;
+    let err = TextureTarget::Texture2D.get_wrap().unwrap_err();
+    assert_eq!(err.to_string(), "0x1234 is not a valid WrapMode");
+}
+
+#[test]
+#[should_panic(expected = "TextureBorderColor takes 4 values")]
+fn short_vector_param_panics() {
+    let _guard = setup();
+    sgl::tex_parameter_fv(
+        TextureTarget::Texture2D,
+        TextureProp::TextureBorderColor,
+        &[0.0; 3],
+    );
+}
+
+#[test]
+#[should_panic(expected = "TextureMinFilter takes 1 values")]
+fn long_scalar_param_panics() {
+    let _guard = setup();
+    let mut params = [0; 2];
+    sgl::get_tex_parameter_iv(
+        TextureTarget::Texture2D,
+        TextureProp::TextureMinFilter,
+        &mut params,
+    );
+}
@@ -0,0 +1,285 @@
+//! Checks the mip level and size validation of immutable texture storage and texture views
+//! against a stubbed GL loader.
+
+mod common;
+
+use std::{
+    ffi::c_void,
+    ptr::null,
+    sync::{
+        atomic::{AtomicU32, AtomicUsize, Ordering},
+        Mutex, MutexGuard,
+    },
+};
+
+use sgl::{gen_texture, InternalFormat, Texture, TextureTarget};
+
+const TEXTURE_TARGET: u32 = 0x1006;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+/// What `glGetTextureParameteriv` answers for `GL_TEXTURE_TARGET`
+static CREATED_TARGET: AtomicU32 = AtomicU32::new(0);
+static TARGET_QUERIES: AtomicUsize = AtomicUsize::new(0);
+static VIEWS: Mutex<Vec<[u32; 8]>> = Mutex::new(Vec::new());
+
+extern "system" fn tex_storage_1d(_: u32, _: i32, _: u32, _: i32) {
+    ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+}
+
+extern "system" fn tex_storage_2d(_: u32, _: i32, _: u32, _: i32, _: i32) {
+    ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+}
+
+extern "system" fn tex_storage_3d(_: u32, _: i32, _: u32, _: i32, _: i32, _: i32) {
+    ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+}
+
+extern "system" fn tex_storage_2d_multisample(_: u32, _: i32, _: u32, _: i32, _: i32, _: u8) {
+    ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+}
+
+extern "system" fn get_texture_parameter_iv(_: u32, pname: u32, params: *mut i32) {
+    assert_eq!(pname, TEXTURE_TARGET);
+    TARGET_QUERIES.fetch_add(1, Ordering::SeqCst);
+    unsafe { *params = CREATED_TARGET.load(Ordering::SeqCst) as i32 };
+}
+
+#[allow(clippy::too_many_arguments)]
+extern "system" fn texture_view(
+    view: u32,
+    target: u32,
+    original: u32,
+    format: u32,
+    min_level: u32,
+    num_levels: u32,
+    min_layer: u32,
+    num_layers: u32,
+) {
+    VIEWS.lock().unwrap().push([
+        view, target, original, format, min_level, num_levels, min_layer, num_layers,
+    ]);
+}
+
+fn stubs(name: &str) -> *const c_void {
+    match name {
+        "glGenTextures" => common::gen_names as *const c_void,
+        "glTexStorage1D" => tex_storage_1d as *const c_void,
+        "glTexStorage2D" | "glTextureStorage2D" => tex_storage_2d as *const c_void,
+        "glTexStorage3D" => tex_storage_3d as *const c_void,
+        "glTexStorage2DMultisample" => tex_storage_2d_multisample as *const c_void,
+        "glGetTextureParameteriv" => get_texture_parameter_iv as *const c_void,
+        "glTextureView" => texture_view as *const c_void,
+        _ => null(),
+    }
+}
+
+fn setup() -> MutexGuard<'static, ()> {
+    let guard = common::setup(stubs);
+    ALLOCATIONS.store(0, Ordering::SeqCst);
+    CREATED_TARGET.store(0x0DE1, Ordering::SeqCst); // GL_TEXTURE_2D
+    TARGET_QUERIES.store(0, Ordering::SeqCst);
+    VIEWS.lock().unwrap().clear();
+    guard
+}
+
+#[test]
+fn full_mip_chains_are_accepted() {
+    let _guard = setup();
+    sgl::tex_storage_1d(9, InternalFormat::RGBA8, 256);
+    sgl::tex_storage_2d(TextureTarget::Texture2D, 9, InternalFormat::RGBA8, 200, 256);
+    sgl::tex_storage_3d(TextureTarget::Texture3D, 6, InternalFormat::RGBA8, 4, 4, 32);
+    sgl::tex_storage_2d(
+        TextureTarget::TextureCubeMap,
+        4,
+        InternalFormat::RGBA8,
+        8,
+        8,
+    );
+    sgl::tex_storage_2d(
+        TextureTarget::TextureRectangle,
+        1,
+        InternalFormat::RGBA8,
+        8,
+        3,
+    );
+    assert_eq!(ALLOCATIONS.load(Ordering::SeqCst), 5);
+}
+
+#[test]
+#[should_panic(expected = "10 mip levels requested but a 256x256x1 texture has 1 to 9")]
+fn too_many_levels_panics() {
+    let _guard = setup();
+    sgl::tex_storage_2d(
+        TextureTarget::Texture2D,
+        10,
+        InternalFormat::RGBA8,
+        256,
+        256,
+    );
+}
+
+#[test]
+#[should_panic(expected = "texture storage can't be empty, got 0x1x1")]
+fn empty_storage_panics() {
+    let _guard = setup();
+    sgl::tex_storage_1d(1, InternalFormat::RGBA8, 0);
+}
+
+#[test]
+fn array_layers_dont_limit_levels() {
+    let _guard = setup();
+    sgl::tex_storage_2d(
+        TextureTarget::Texture1DArray,
+        3,
+        InternalFormat::RGBA8,
+        4,
+        64,
+    );
+    sgl::tex_storage_3d(
+        TextureTarget::Texture2DArray,
+        3,
+        InternalFormat::RGBA8,
+        4,
+        4,
+        64,
+    );
+    sgl::tex_storage_3d(
+        TextureTarget::TextureCubeMapArray,
+        3,
+        InternalFormat::RGBA8,
+        4,
+        4,
+        12,
+    );
+    assert_eq!(ALLOCATIONS.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+#[should_panic(expected = "Texture3D doesn't have 2D storage")]
+fn wrong_2d_target_panics() {
+    let _guard = setup();
+    sgl::tex_storage_2d(TextureTarget::Texture3D, 1, InternalFormat::RGBA8, 4, 4);
+}
+
+#[test]
+#[should_panic(expected = "rectangle textures have exactly 1 mip level, got 2")]
+fn mipmapped_rectangle_panics() {
+    let _guard = setup();
+    sgl::tex_storage_2d(
+        TextureTarget::TextureRectangle,
+        2,
+        InternalFormat::RGBA8,
+        4,
+        4,
+    );
+}
+
+#[test]
+#[should_panic(expected = "cube map faces must be square, got 8x4")]
+fn non_square_cube_map_panics() {
+    let _guard = setup();
+    sgl::tex_storage_2d(
+        TextureTarget::TextureCubeMap,
+        1,
+        InternalFormat::RGBA8,
+        8,
+        4,
+    );
+}
+
+#[test]
+#[should_panic(expected = "cube map array depth must be a multiple of 6, got 8")]
+fn partial_cube_map_array_panics() {
+    let _guard = setup();
+    sgl::tex_storage_3d(
+        TextureTarget::TextureCubeMapArray,
+        1,
+        InternalFormat::RGBA8,
+        4,
+        4,
+        8,
+    );
+}
+
+#[test]
+#[should_panic(expected = "multisample storage needs at least 1 sample")]
+fn multisample_storage_without_samples_panics() {
+    let _guard = setup();
+    sgl::tex_storage_2d_multisample(0, InternalFormat::RGBA8, 4, 4, true);
+}
+
+#[test]
+fn dsa_storage_checks_the_created_target() {
+    let _guard = setup();
+    let texture = gen_texture();
+    sgl::texture_storage_2d(texture, 3, InternalFormat::RGBA8, 4, 4);
+    assert_eq!(TARGET_QUERIES.load(Ordering::SeqCst), 1);
+
+    CREATED_TARGET.store(0x8C18, Ordering::SeqCst); // GL_TEXTURE_1D_ARRAY
+    sgl::texture_storage_2d(texture, 3, InternalFormat::RGBA8, 4, 64);
+    assert_eq!(TARGET_QUERIES.load(Ordering::SeqCst), 2);
+    assert_eq!(ALLOCATIONS.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+#[should_panic(expected = "Texture2DMultiSample doesn't have 2D storage")]
+fn dsa_storage_of_a_multisample_texture_panics() {
+    let _guard = setup();
+    CREATED_TARGET.store(0x9100, Ordering::SeqCst); // GL_TEXTURE_2D_MULTISAMPLE
+    sgl::texture_storage_2d(gen_texture(), 1, InternalFormat::RGBA8, 4, 4);
+}
+
+#[test]
+#[should_panic(expected = "4 mip levels requested but a 4x1x1 texture has 1 to 3")]
+fn dsa_1d_array_layers_dont_add_levels() {
+    let _guard = setup();
+    CREATED_TARGET.store(0x8C18, Ordering::SeqCst); // GL_TEXTURE_1D_ARRAY
+    sgl::texture_storage_2d(gen_texture(), 4, InternalFormat::RGBA8, 4, 64);
+}
+
+#[test]
+fn view_ranges_are_passed_as_first_and_count() {
+    let _guard = setup();
+    let (original, view) = (gen_texture(), gen_texture());
+    sgl::texture_view(
+        view,
+        TextureTarget::Texture2DArray,
+        original,
+        InternalFormat::SRGB8Alpha8,
+        1..3,
+        2..6,
+    );
+    assert_eq!(
+        *VIEWS.lock().unwrap(),
+        [[*view, 0x8C1A, *original, 0x8C43, 1, 2, 2, 4]] // GL_TEXTURE_2D_ARRAY, GL_SRGB8_ALPHA8
+    );
+}
+
+#[test]
+#[should_panic(expected = "texture view needs at least 1 level")]
+fn empty_view_panics() {
+    let _guard = setup();
+    let (original, view) = (gen_texture(), gen_texture());
+    sgl::texture_view(
+        view,
+        TextureTarget::Texture2D,
+        original,
+        InternalFormat::RGBA8,
+        1..1,
+        0..1,
+    );
+}
+
+#[test]
+#[should_panic(expected = "texture views can't involve Texture::NONE")]
+fn view_of_no_texture_panics() {
+    let _guard = setup();
+    sgl::texture_view(
+        gen_texture(),
+        TextureTarget::Texture2D,
+        Texture::NONE,
+        InternalFormat::RGBA8,
+        0..1,
+        0..1,
+    );
+}
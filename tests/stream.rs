@@ -0,0 +1,191 @@
+//! Drives `StreamBuffer` against a stubbed GL loader to check which regions get fenced,
+//! waited on and orphaned.
+
+mod common;
+
+use std::{
+    ffi::c_void,
+    ptr::{addr_of_mut, null},
+    sync::{
+        atomic::{AtomicU32, AtomicUsize, Ordering},
+        Mutex, MutexGuard,
+    },
+};
+
+use sgl::{BufferKind, StreamBuffer};
+
+const STORAGE_SIZE: usize = 256;
+/// Drivers align mappings to at least `GL_MIN_MAP_BUFFER_ALIGNMENT`, which is 64
+#[repr(align(64))]
+struct Storage([u8; STORAGE_SIZE]);
+
+static mut STORAGE: Storage = Storage([0; STORAGE_SIZE]);
+
+static BUFFER_SIZE: AtomicUsize = AtomicUsize::new(0);
+static NEXT_FENCE: AtomicUsize = AtomicUsize::new(1);
+static WAITED: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+static ORPHANED: AtomicUsize = AtomicUsize::new(0);
+static WAIT_RESULT: AtomicU32 = AtomicU32::new(ALREADY_SIGNALED);
+
+const ALREADY_SIGNALED: u32 = 0x911A;
+
+extern "system" fn get_integer(pname: u32, data: *mut i32) {
+    let value = match pname {
+        0x821B => 4, // GL_MAJOR_VERSION
+        0x821C => 6, // GL_MINOR_VERSION
+        _ => 0,
+    };
+    unsafe { *data = value };
+}
+
+extern "system" fn noop_delete(_: i32, _: *const u32) {}
+
+extern "system" fn bind_buffer(_: u32, _: u32) {}
+
+extern "system" fn buffer_storage(_: u32, size: isize, _: *const c_void, _: u32) {
+    BUFFER_SIZE.store(size as usize, Ordering::SeqCst);
+}
+
+extern "system" fn buffer_data(_: u32, size: isize, _: *const c_void, _: u32) {
+    BUFFER_SIZE.store(size as usize, Ordering::SeqCst);
+    ORPHANED.fetch_add(1, Ordering::SeqCst);
+}
+
+extern "system" fn get_buffer_parameter(_: u32, _: u32, data: *mut i64) {
+    unsafe { *data = BUFFER_SIZE.load(Ordering::SeqCst) as i64 };
+}
+
+extern "system" fn map_buffer_range(_: u32, offset: isize, _: isize, _: u32) -> *mut c_void {
+    unsafe { (addr_of_mut!(STORAGE.0) as *mut u8).add(offset as usize) as *mut c_void }
+}
+
+extern "system" fn unmap_buffer(_: u32) -> u8 {
+    1
+}
+
+extern "system" fn fence_sync(_: u32, _: u32) -> *const c_void {
+    NEXT_FENCE.fetch_add(1, Ordering::SeqCst) as *const c_void
+}
+
+extern "system" fn client_wait_sync(sync: *const c_void, _: u32, _: u64) -> u32 {
+    WAITED.lock().unwrap().push(sync as usize);
+    WAIT_RESULT.load(Ordering::SeqCst)
+}
+
+extern "system" fn delete_sync(_: *const c_void) {}
+
+fn stubs(name: &str) -> *const c_void {
+    match name {
+        "glGetIntegerv" => get_integer as *const c_void,
+        "glGenBuffers" => common::gen_names as *const c_void,
+        "glDeleteBuffers" => noop_delete as *const c_void,
+        "glBindBuffer" => bind_buffer as *const c_void,
+        "glBufferStorage" => buffer_storage as *const c_void,
+        "glBufferData" => buffer_data as *const c_void,
+        "glGetBufferParameteri64v" => get_buffer_parameter as *const c_void,
+        "glMapBufferRange" => map_buffer_range as *const c_void,
+        "glUnmapBuffer" => unmap_buffer as *const c_void,
+        "glFenceSync" => fence_sync as *const c_void,
+        "glClientWaitSync" => client_wait_sync as *const c_void,
+        "glDeleteSync" => delete_sync as *const c_void,
+        _ => null(),
+    }
+}
+
+fn setup() -> MutexGuard<'static, ()> {
+    let guard = common::setup(stubs);
+    NEXT_FENCE.store(1, Ordering::SeqCst);
+    WAITED.lock().unwrap().clear();
+    ORPHANED.store(0, Ordering::SeqCst);
+    WAIT_RESULT.store(ALREADY_SIGNALED, Ordering::SeqCst);
+    guard
+}
+
+fn waited() -> Vec<usize> {
+    WAITED.lock().unwrap().clone()
+}
+
+#[test]
+fn persistent_waits_only_on_overwritten_frames() {
+    let _guard = setup();
+    let mut stream = StreamBuffer::new(BufferKind::Array, 64);
+    assert!(stream.is_persistent());
+
+    assert_eq!(stream.write(&[1u8; 32]), 0);
+    stream.end_frame(); // fence 1 guards 0..32
+    assert_eq!(stream.write(&[2u8; 32]), 32);
+    stream.end_frame(); // fence 2 guards 32..64
+    assert!(waited().is_empty());
+
+    // wraps around onto the first frame only
+    assert_eq!(stream.write(&[3u8; 16]), 0);
+    assert_eq!(waited(), vec![1]);
+
+    assert_eq!(stream.write(&[4u8; 16]), 16);
+    assert_eq!(waited(), vec![1]);
+
+    stream.end_frame(); // fence 3 guards 0..32
+    assert_eq!(stream.write(&[5u8; 8]), 32);
+    assert_eq!(waited(), vec![1, 2]);
+}
+
+#[test]
+fn persistent_aligns_and_skips_to_start() {
+    let _guard = setup();
+    let mut stream = StreamBuffer::new(BufferKind::Array, 64);
+
+    assert_eq!(stream.write(&[0u8; 3]), 0);
+    assert_eq!(stream.write(&[0u32; 2]), 4);
+    stream.end_frame();
+
+    // doesn't fit in the 52 bytes left, so starts over at 0 instead of splitting
+    assert_eq!(stream.write(&[0u32; 14]), 0);
+    assert_eq!(waited(), vec![1]);
+}
+
+#[test]
+#[should_panic(expected = "a single frame allocated more than the whole stream buffer")]
+fn persistent_frame_larger_than_buffer_panics() {
+    let _guard = setup();
+    let mut stream = StreamBuffer::new(BufferKind::Array, 64);
+    stream.write(&[0u8; 48]);
+    stream.write(&[0u8; 48]);
+}
+
+#[test]
+fn orphaning_reallocates_when_full() {
+    let _guard = setup();
+    let mut stream = StreamBuffer::new_orphaning(BufferKind::Array, 64);
+    assert!(!stream.is_persistent());
+    assert_eq!(ORPHANED.load(Ordering::SeqCst), 1);
+
+    assert_eq!(stream.write(&[1u8; 48]), 0);
+    stream.end_frame();
+    assert_eq!(ORPHANED.load(Ordering::SeqCst), 1);
+
+    assert_eq!(stream.write(&[2u8; 32]), 0);
+    assert_eq!(ORPHANED.load(Ordering::SeqCst), 2);
+    assert_eq!(stream.write(&[3u8; 32]), 32);
+    assert_eq!(ORPHANED.load(Ordering::SeqCst), 2);
+    assert!(waited().is_empty());
+}
+
+#[test]
+#[should_panic(expected = "waiting on a stream buffer fence failed: WaitFailed")]
+fn persistent_failed_wait_panics() {
+    let _guard = setup();
+    let mut stream = StreamBuffer::new(BufferKind::Array, 64);
+    stream.write(&[0u8; 64]);
+    stream.end_frame();
+
+    WAIT_RESULT.store(0x911D, Ordering::SeqCst); // GL_WAIT_FAILED
+    stream.write(&[0u8; 16]);
+}
+
+#[test]
+#[should_panic(expected = "stream allocation is too large")]
+fn oversized_allocation_panics() {
+    let _guard = setup();
+    let mut stream = StreamBuffer::new(BufferKind::Array, 64);
+    stream.allocate::<u64>(usize::MAX / 4);
+}
@@ -0,0 +1,211 @@
+//! Checks `tex_image_*` payload validation against a stubbed GL loader, with the unpack state
+//! reset to its defaults before every test.
+
+mod common;
+
+use std::{
+    ffi::c_void,
+    ptr::null,
+    sync::{
+        atomic::{AtomicI32, AtomicUsize, Ordering},
+        MutexGuard,
+    },
+};
+
+use sgl::{pixel_size, ImageTarget2D, ImageTarget3D, InternalFormat, PixelFormat, PixelType};
+
+const UNPACK_ALIGNMENT: u32 = 0x0CF5;
+const UNPACK_ROW_LENGTH: u32 = 0x0CF2;
+const UNPACK_SKIP_ROWS: u32 = 0x0CF3;
+const UNPACK_SKIP_PIXELS: u32 = 0x0CF4;
+const UNPACK_SKIP_IMAGES: u32 = 0x806D;
+const UNPACK_IMAGE_HEIGHT: u32 = 0x806E;
+const PIXEL_UNPACK_BUFFER_BINDING: u32 = 0x88EF;
+
+static UPLOADS: AtomicUsize = AtomicUsize::new(0);
+static UNPACK: [(u32, AtomicI32); 7] = [
+    (UNPACK_ALIGNMENT, AtomicI32::new(4)),
+    (UNPACK_ROW_LENGTH, AtomicI32::new(0)),
+    (UNPACK_SKIP_ROWS, AtomicI32::new(0)),
+    (UNPACK_SKIP_PIXELS, AtomicI32::new(0)),
+    (UNPACK_SKIP_IMAGES, AtomicI32::new(0)),
+    (UNPACK_IMAGE_HEIGHT, AtomicI32::new(0)),
+    (PIXEL_UNPACK_BUFFER_BINDING, AtomicI32::new(0)),
+];
+
+fn set_unpack(pname: u32, value: i32) {
+    let (_, state) = UNPACK.iter().find(|(p, _)| *p == pname).unwrap();
+    state.store(value, Ordering::SeqCst);
+}
+
+extern "system" fn get_integer(pname: u32, data: *mut i32) {
+    let value = UNPACK
+        .iter()
+        .find(|(p, _)| *p == pname)
+        .map_or(0, |(_, state)| state.load(Ordering::SeqCst));
+    unsafe { *data = value };
+}
+
+extern "system" fn tex_image_2d(
+    _: u32,
+    _: i32,
+    _: i32,
+    _: i32,
+    _: i32,
+    _: i32,
+    _: u32,
+    _: u32,
+    _: *const c_void,
+) {
+    UPLOADS.fetch_add(1, Ordering::SeqCst);
+}
+
+extern "system" fn tex_image_3d(
+    _: u32,
+    _: i32,
+    _: i32,
+    _: i32,
+    _: i32,
+    _: i32,
+    _: i32,
+    _: u32,
+    _: u32,
+    _: *const c_void,
+) {
+    UPLOADS.fetch_add(1, Ordering::SeqCst);
+}
+
+fn stubs(name: &str) -> *const c_void {
+    match name {
+        "glGetIntegerv" => get_integer as *const c_void,
+        "glTexImage2D" => tex_image_2d as *const c_void,
+        "glTexImage3D" => tex_image_3d as *const c_void,
+        _ => null(),
+    }
+}
+
+fn setup() -> MutexGuard<'static, ()> {
+    let guard = common::setup(stubs);
+    UPLOADS.store(0, Ordering::SeqCst);
+    for (pname, state) in &UNPACK {
+        let default = if *pname == UNPACK_ALIGNMENT { 4 } else { 0 };
+        state.store(default, Ordering::SeqCst);
+    }
+    guard
+}
+
+fn upload_rgb(width: i32, height: i32, data: &[u8]) {
+    sgl::tex_image_2d(
+        ImageTarget2D::Texture2D,
+        0,
+        InternalFormat::RGB8,
+        width,
+        height,
+        PixelFormat::RGB,
+        PixelType::UnsignedByte,
+        data,
+    );
+}
+
+fn upload_rgba_3d(width: i32, height: i32, depth: i32, data: &[u8]) {
+    sgl::tex_image_3d(
+        ImageTarget3D::Texture3D,
+        0,
+        InternalFormat::RGBA8,
+        width,
+        height,
+        depth,
+        PixelFormat::RGBA,
+        PixelType::UnsignedByte,
+        data,
+    );
+}
+
+#[test]
+fn pixel_sizes() {
+    assert_eq!(pixel_size(PixelFormat::RGBA, PixelType::Float), 16);
+    assert_eq!(pixel_size(PixelFormat::RGB, PixelType::UnsignedShort565), 2);
+    assert_eq!(
+        pixel_size(PixelFormat::DepthStencil, PixelType::UnsignedInt248),
+        4
+    );
+}
+
+#[test]
+#[should_panic(expected = "RGB pixels can't be stored as UnsignedInt8888")]
+fn mismatched_packed_type() {
+    pixel_size(PixelFormat::RGB, PixelType::UnsignedInt8888);
+}
+
+#[test]
+fn rows_are_padded_except_the_last() {
+    let _guard = setup();
+    // 3 byte rows padded to 4, the last one isn't
+    upload_rgb(1, 3, &[0; 11]);
+    assert_eq!(UPLOADS.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+#[should_panic(expected = "a 2x2x1 RGB/UnsignedByte image needs 14 bytes, got 12")]
+fn short_payload_panics() {
+    let _guard = setup();
+    upload_rgb(2, 2, &[0; 12]);
+}
+
+#[test]
+fn row_length_and_skips_are_applied() {
+    let _guard = setup();
+    set_unpack(UNPACK_ROW_LENGTH, 4);
+    set_unpack(UNPACK_SKIP_PIXELS, 1);
+    set_unpack(UNPACK_SKIP_ROWS, 1);
+    // 12 byte rows, skipping a row and a pixel, then a full row and the 2 pixels of the last
+    upload_rgb(2, 2, &[0; 33]);
+    assert_eq!(UPLOADS.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+#[should_panic(expected = "a 2x2x1 RGB/UnsignedByte image needs 33 bytes, got 32")]
+fn short_payload_for_row_length_panics() {
+    let _guard = setup();
+    set_unpack(UNPACK_ROW_LENGTH, 4);
+    set_unpack(UNPACK_SKIP_PIXELS, 1);
+    set_unpack(UNPACK_SKIP_ROWS, 1);
+    upload_rgb(2, 2, &[0; 32]);
+}
+
+#[test]
+fn image_height_and_skip_images_only_apply_to_3d() {
+    let _guard = setup();
+    set_unpack(UNPACK_IMAGE_HEIGHT, 3);
+    set_unpack(UNPACK_SKIP_IMAGES, 1);
+    // 12 byte images, one skipped, then a full one and the 2 rows of the last
+    upload_rgba_3d(1, 2, 2, &[0; 32]);
+    sgl::tex_image_2d(
+        ImageTarget2D::Texture2D,
+        0,
+        InternalFormat::RGBA8,
+        1,
+        2,
+        PixelFormat::RGBA,
+        PixelType::UnsignedByte,
+        &[0u8; 8],
+    );
+    assert_eq!(UPLOADS.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+#[should_panic(expected = "a 1x2x2 RGBA/UnsignedByte image needs 32 bytes, got 28")]
+fn short_payload_for_image_height_panics() {
+    let _guard = setup();
+    set_unpack(UNPACK_IMAGE_HEIGHT, 3);
+    set_unpack(UNPACK_SKIP_IMAGES, 1);
+    upload_rgba_3d(1, 2, 2, &[0; 28]);
+}
+
+#[test]
+#[should_panic(expected = "can't upload from client memory while a PixelUnpack buffer is bound")]
+fn bound_unpack_buffer_panics() {
+    let _guard = setup();
+    set_unpack(PIXEL_UNPACK_BUFFER_BINDING, 1);
+    upload_rgb(1, 1, &[0; 3]);
+}
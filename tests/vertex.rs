@@ -0,0 +1,72 @@
+#![cfg(feature = "derive")]
+
+use sgl::{AttributeKind, Type, Vertex, VertexAttribute};
+
+#[repr(C)]
+#[derive(Clone, Copy, Vertex)]
+struct Simple {
+    position: [f32; 3],
+    #[vertex(normalized)]
+    color: [u8; 4],
+    material: u32,
+    #[vertex(float)]
+    weight: i16,
+    precise: [f64; 4],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Vertex)]
+struct Tuple([f32; 2], [f32; 2]);
+
+#[test]
+fn named_fields() {
+    assert_eq!(
+        Simple::ATTRIBUTES,
+        &[
+            VertexAttribute {
+                size: 3,
+                ty: Type::Float,
+                kind: AttributeKind::Float,
+                normalized: false,
+                offset: 0,
+            },
+            VertexAttribute {
+                size: 4,
+                ty: Type::UnsignedByte,
+                kind: AttributeKind::Float,
+                normalized: true,
+                offset: 12,
+            },
+            VertexAttribute {
+                size: 1,
+                ty: Type::UnsignedInt,
+                kind: AttributeKind::Integer,
+                normalized: false,
+                offset: 16,
+            },
+            VertexAttribute {
+                size: 1,
+                ty: Type::Short,
+                kind: AttributeKind::Float,
+                normalized: false,
+                offset: 20,
+            },
+            VertexAttribute {
+                size: 4,
+                ty: Type::Double,
+                kind: AttributeKind::Double,
+                normalized: false,
+                offset: 24,
+            },
+        ]
+    );
+
+    let locations: u32 = Simple::ATTRIBUTES.iter().map(|a| a.locations()).sum();
+    assert_eq!(locations, 6);
+}
+
+#[test]
+fn tuple_fields() {
+    let offsets: Vec<usize> = Tuple::ATTRIBUTES.iter().map(|a| a.offset).collect();
+    assert_eq!(offsets, vec![0, 8]);
+}